@@ -0,0 +1,44 @@
+use tonic_build::manual::{
+    Builder,
+    Method,
+    Service,
+};
+
+static CODEC_PATH: &str = "tonic_prost::ProstCodec";
+
+pub fn main() {
+    let service = Service::builder()
+        .name("EventStore")
+        .package("dcb.v1")
+        .method(
+            Method::builder()
+                .name("read")
+                .route_name("Read")
+                .input_type("crate::grpc::proto::ReadRequest")
+                .output_type("crate::grpc::proto::ReadResponse")
+                .codec_path(CODEC_PATH)
+                .build(),
+        )
+        .method(
+            Method::builder()
+                .name("append")
+                .route_name("Append")
+                .input_type("crate::grpc::proto::AppendRequest")
+                .output_type("crate::grpc::proto::AppendResponse")
+                .codec_path(CODEC_PATH)
+                .build(),
+        )
+        .method(
+            Method::builder()
+                .name("subscribe")
+                .route_name("Subscribe")
+                .input_type("crate::grpc::proto::SubscribeRequest")
+                .output_type("crate::grpc::proto::SequencedEvent")
+                .codec_path(CODEC_PATH)
+                .server_streaming()
+                .build(),
+        )
+        .build();
+
+    Builder::new().build_client(true).compile(&[service]);
+}
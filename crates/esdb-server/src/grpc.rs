@@ -0,0 +1,517 @@
+use std::pin::Pin;
+
+use axum::Router;
+use esdb::{
+    AsyncStream,
+    Condition,
+    Event,
+    Position,
+    Query,
+    Selector,
+    SequencedEvent,
+    Specifier,
+    Tag,
+    TagKey,
+};
+use futures::{
+    Stream,
+    StreamExt as _,
+    TryStreamExt as _,
+};
+use tonic::{
+    Request,
+    Response,
+    Status,
+    service::Routes,
+};
+
+use crate::{
+    ApiError,
+    grpc::{
+        generated::event_store_server::{
+            EventStore,
+            EventStoreServer,
+        },
+        proto::{
+            AppendRequest,
+            AppendResponse,
+            ReadRequest,
+            ReadResponse,
+            SubscribeRequest,
+        },
+    },
+    signature,
+    timestamp,
+};
+
+// =================================================================================================
+// gRPC
+// =================================================================================================
+
+// Router
+
+pub fn router(stream: AsyncStream) -> Router {
+    Routes::new(EventStoreServer::new(Service { stream })).into_axum_router()
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Service
+
+struct Service {
+    stream: AsyncStream,
+}
+
+#[tonic::async_trait]
+impl EventStore for Service {
+    type SubscribeStream =
+        Pin<Box<dyn Stream<Item = Result<proto::SequencedEvent, Status>> + Send + 'static>>;
+
+    async fn read(&self, request: Request<ReadRequest>) -> Result<Response<ReadResponse>, Status> {
+        let request = request.into_inner();
+        let options = request.options.unwrap_or_default();
+
+        let query = Query::from(request.query.unwrap_or_default());
+        let position = options.from.map(Position::from);
+        let limit = options.limit.map_or(usize::MAX, |limit| {
+            usize::try_from(limit).unwrap_or(usize::MAX)
+        });
+
+        let events = self
+            .stream
+            .query(query, position)
+            .take(limit)
+            .map_ok(Into::into)
+            .try_collect()
+            .await
+            .map_err(ApiError::from)?;
+
+        Ok(Response::new(ReadResponse { events }))
+    }
+
+    async fn append(
+        &self,
+        request: Request<AppendRequest>,
+    ) -> Result<Response<AppendResponse>, Status> {
+        let request = request.into_inner();
+
+        let events = request
+            .events
+            .into_iter()
+            .map(Event::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let position = match request.condition {
+            Some(condition) => {
+                self.stream
+                    .append_if(events, Condition::from(condition))
+                    .await
+            }
+            None => self.stream.append(events).await,
+        };
+
+        let position = position.map_err(ApiError::from)?.map(Position::value);
+
+        Ok(Response::new(AppendResponse { position }))
+    }
+
+    async fn subscribe(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let request = request.into_inner();
+
+        let query = Query::from(request.query.unwrap_or_default());
+        let position = request.from.map(Position::from);
+
+        let events = self
+            .stream
+            .subscribe(query, position)
+            .map_ok(Into::into)
+            .map_err(|err| Status::from(ApiError::from(err)));
+
+        Ok(Response::new(Box::pin(events)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Conversions
+
+impl TryFrom<proto::Event> for Event {
+    type Error = Status;
+
+    fn try_from(event: proto::Event) -> Result<Self, Self::Error> {
+        let version = u8::try_from(event.version)
+            .map_err(|_| Status::invalid_argument("event version out of range"))?;
+
+        let signature = signature(event.key_id, event.signature.as_deref())?;
+
+        let tags = event.tags.into_iter().map(Tag::from).collect::<Vec<_>>();
+
+        let mut event = Event::new(event.data, (event.r#type, version), tags);
+
+        event.signature = signature;
+
+        Ok(event)
+    }
+}
+
+impl From<SequencedEvent> for proto::SequencedEvent {
+    fn from(event: SequencedEvent) -> Self {
+        let position = event.position.value();
+        let archived = event.archived.map(timestamp);
+        let redacted = event.redacted;
+        let key_id = event.provenance.key_id().map(ToOwned::to_owned);
+        let verified = key_id.as_ref().map(|_| event.provenance.is_verified());
+        let signature = event
+            .event
+            .signature
+            .map(|(_, signature)| signature.to_bytes().to_vec());
+        let (identifier, version) = event.event.descriptor.take();
+
+        let event = proto::Event {
+            r#type: identifier.value().to_owned(),
+            data: event.event.data,
+            tags: event
+                .event
+                .tags
+                .iter()
+                .map(|tag| tag.value().to_owned())
+                .collect(),
+            version: version.value().into(),
+            key_id,
+            signature,
+        };
+
+        Self {
+            event: Some(event),
+            position,
+            archived,
+            redacted,
+            verified,
+        }
+    }
+}
+
+impl From<proto::Query> for Query {
+    fn from(query: proto::Query) -> Self {
+        if query.items.is_empty() {
+            return Query::all();
+        }
+
+        let selectors = query
+            .items
+            .into_iter()
+            .map(Selector::from)
+            .collect::<Vec<_>>();
+
+        Query::from(selectors)
+    }
+}
+
+impl From<proto::QueryItem> for Selector {
+    fn from(item: proto::QueryItem) -> Self {
+        let specifiers = item
+            .types
+            .into_iter()
+            .map(|identifier| Specifier::from((identifier, None)))
+            .collect::<Vec<_>>();
+
+        let tags = item.tags.into_iter().map(Tag::from).collect::<Vec<_>>();
+
+        let tag_keys = item
+            .tag_keys
+            .into_iter()
+            .map(|tag_key| TagKey::with_values(tag_key.key, tag_key.values))
+            .collect::<Vec<_>>();
+
+        Selector::from((specifiers, tags, item.tag_prefixes, tag_keys))
+    }
+}
+
+impl From<proto::AppendCondition> for Condition {
+    fn from(condition: proto::AppendCondition) -> Self {
+        let query = Query::from(condition.fail_if_events_match.unwrap_or_default());
+        let position = condition.after.map(Position::from);
+
+        Condition::from((query, position))
+    }
+}
+
+impl From<ApiError> for Status {
+    fn from(err: ApiError) -> Self {
+        match err {
+            ApiError::BadRequest(message) => Status::invalid_argument(message),
+            ApiError::Conflict(message) => Status::failed_precondition(message),
+            ApiError::Forbidden(message) => Status::permission_denied(message),
+            ApiError::Internal(message) => Status::internal(message),
+            ApiError::TooManyRequests(message) => Status::resource_exhausted(message),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Generated
+
+#[allow(clippy::all, clippy::pedantic)]
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/dcb.v1.EventStore.rs"));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Messages (see proto/dcb.proto)
+
+pub mod proto {
+    use prost::Message;
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct Event {
+        #[prost(string, tag = "1")]
+        pub r#type: String,
+        #[prost(bytes = "vec", tag = "2")]
+        pub data: Vec<u8>,
+        #[prost(string, repeated, tag = "3")]
+        pub tags: Vec<String>,
+        #[prost(uint32, tag = "4")]
+        pub version: u32,
+        #[prost(string, optional, tag = "5")]
+        pub key_id: Option<String>,
+        #[prost(bytes = "vec", optional, tag = "6")]
+        pub signature: Option<Vec<u8>>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct SequencedEvent {
+        #[prost(message, optional, tag = "1")]
+        pub event: Option<Event>,
+        #[prost(uint64, tag = "2")]
+        pub position: u64,
+        #[prost(uint64, optional, tag = "3")]
+        pub archived: Option<u64>,
+        #[prost(bool, tag = "4")]
+        pub redacted: bool,
+        #[prost(bool, optional, tag = "5")]
+        pub verified: Option<bool>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct QueryItem {
+        #[prost(string, repeated, tag = "1")]
+        pub types: Vec<String>,
+        #[prost(string, repeated, tag = "2")]
+        pub tags: Vec<String>,
+        #[prost(string, repeated, tag = "3")]
+        pub tag_prefixes: Vec<String>,
+        #[prost(message, repeated, tag = "4")]
+        pub tag_keys: Vec<TagKey>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct TagKey {
+        #[prost(string, tag = "1")]
+        pub key: String,
+        #[prost(string, repeated, tag = "2")]
+        pub values: Vec<String>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct Query {
+        #[prost(message, repeated, tag = "1")]
+        pub items: Vec<QueryItem>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct AppendCondition {
+        #[prost(message, optional, tag = "1")]
+        pub fail_if_events_match: Option<Query>,
+        #[prost(uint64, optional, tag = "2")]
+        pub after: Option<u64>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct ReadOptions {
+        #[prost(uint64, optional, tag = "1")]
+        pub from: Option<u64>,
+        #[prost(uint64, optional, tag = "2")]
+        pub limit: Option<u64>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct ReadRequest {
+        #[prost(message, optional, tag = "1")]
+        pub query: Option<Query>,
+        #[prost(message, optional, tag = "2")]
+        pub options: Option<ReadOptions>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct ReadResponse {
+        #[prost(message, repeated, tag = "1")]
+        pub events: Vec<SequencedEvent>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct AppendRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub events: Vec<Event>,
+        #[prost(message, optional, tag = "2")]
+        pub condition: Option<AppendCondition>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct AppendResponse {
+        #[prost(uint64, optional, tag = "1")]
+        pub position: Option<u64>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    pub struct SubscribeRequest {
+        #[prost(message, optional, tag = "1")]
+        pub query: Option<Query>,
+        #[prost(uint64, optional, tag = "2")]
+        pub from: Option<u64>,
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use esdb::{
+        Stream,
+        StreamOptions,
+    };
+    use futures::StreamExt as _;
+    use tokio::net::TcpListener;
+    use tonic::{
+        Code,
+        transport::Channel,
+    };
+
+    use crate::{
+        grpc::{
+            generated::event_store_client::EventStoreClient,
+            proto::{
+                AppendCondition,
+                AppendRequest,
+                Event,
+                Query,
+                QueryItem,
+                ReadRequest,
+                SubscribeRequest,
+            },
+        },
+        serve,
+    };
+
+    async fn client() -> Result<EventStoreClient<Channel>, Box<dyn Error>> {
+        let stream = Stream::memory(StreamOptions::default())?;
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+
+        tokio::spawn(async move { serve(listener, stream).await.ok() });
+
+        Ok(EventStoreClient::connect(format!("http://{address}")).await?)
+    }
+
+    fn query(tag: &str) -> Query {
+        Query {
+            items: vec![QueryItem {
+                tags: vec![tag.to_owned()],
+                ..QueryItem::default()
+            }],
+        }
+    }
+
+    fn append(tag: &str, after: Option<u64>) -> AppendRequest {
+        AppendRequest {
+            events: vec![Event {
+                r#type: "order-placed".to_owned(),
+                data: b"data".to_vec(),
+                tags: vec![tag.to_owned()],
+                ..Event::default()
+            }],
+            condition: Some(AppendCondition {
+                fail_if_events_match: Some(query(tag)),
+                after,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn appends_and_reads_round_trip() -> Result<(), Box<dyn Error>> {
+        let mut client = client().await?;
+
+        let first = client.append(append("order:1", None)).await?.into_inner();
+        let second = client.append(append("order:2", None)).await?.into_inner();
+
+        assert_eq!(first.position, Some(0));
+        assert_eq!(second.position, Some(1));
+
+        let read = ReadRequest {
+            query: Some(query("order:2")),
+            options: None,
+        };
+
+        let events = client.read(read).await?.into_inner().events;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].position, 1);
+        assert_eq!(
+            events[0].event.as_ref().map(|event| event.data.as_slice()),
+            Some(&b"data"[..])
+        );
+
+        let subscribe = SubscribeRequest {
+            query: Some(Query::default()),
+            from: Some(1),
+        };
+
+        let mut events = client.subscribe(subscribe).await?.into_inner();
+
+        client.append(append("order:3", None)).await?;
+
+        let positions = [events.next().await, events.next().await]
+            .into_iter()
+            .map(|event| {
+                event
+                    .transpose()
+                    .map(|event| event.map(|event| event.position))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(positions, [Some(1), Some(2)]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn conflicting_appends_are_rejected() -> Result<(), Box<dyn Error>> {
+        let mut client = client().await?;
+
+        client.append(append("order:1", None)).await?;
+
+        let status = client
+            .append(append("order:1", None))
+            .await
+            .expect_err("conflicting append");
+
+        assert_eq!(status.code(), Code::FailedPrecondition);
+
+        let appended = client
+            .append(append("order:1", Some(0)))
+            .await?
+            .into_inner();
+
+        assert_eq!(appended.position, Some(1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,560 @@
+use std::convert::Infallible;
+
+use axum::{
+    Json,
+    Router,
+    extract::{
+        Query as Parameters,
+        State,
+    },
+    http::StatusCode,
+    response::{
+        IntoResponse,
+        Response,
+        sse::{
+            self,
+            KeepAlive,
+            Sse,
+        },
+    },
+    routing,
+};
+use base64::{
+    Engine as _,
+    engine::general_purpose::STANDARD,
+};
+use esdb::{
+    AsyncStream,
+    Condition,
+    Event,
+    Position,
+    Query,
+    Selector,
+    SequencedEvent,
+    Specifier,
+    Tag,
+    TagKey,
+};
+use futures::{
+    Stream,
+    StreamExt as _,
+    TryStreamExt as _,
+    future,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    ApiError,
+    signature,
+    timestamp,
+};
+
+// =================================================================================================
+// HTTP
+// =================================================================================================
+
+// Router
+
+pub fn router(stream: AsyncStream) -> Router {
+    Router::new()
+        .route("/events", routing::get(read).post(append))
+        .route("/events/subscribe", routing::get(subscribe))
+        .route("/tags", routing::get(tags))
+        .route("/types", routing::get(types))
+        .with_state(stream)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Append
+
+async fn append(
+    State(stream): State<AsyncStream>,
+    Json(request): Json<AppendRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let events = request
+        .events
+        .into_iter()
+        .map(Event::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let position = match request.condition {
+        Some(condition) => stream.append_if(events, Condition::from(condition)).await?,
+        None => stream.append(events).await?,
+    };
+
+    let position = position.map(Position::value);
+
+    Ok((StatusCode::CREATED, Json(AppendResponse { position })))
+}
+
+// Read
+
+async fn read(
+    State(stream): State<AsyncStream>,
+    Parameters(parameters): Parameters<ReadParameters>,
+) -> Result<impl IntoResponse, ApiError> {
+    let query = parameters.query();
+    let position = parameters.from.map(Position::from);
+    let limit = parameters.limit.unwrap_or(usize::MAX);
+
+    let events = stream
+        .query(query, position)
+        .take(limit)
+        .map_ok(EventResponse::from)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    Ok(Json(events))
+}
+
+// Subscribe
+
+async fn subscribe(
+    State(stream): State<AsyncStream>,
+    Parameters(parameters): Parameters<ReadParameters>,
+) -> Sse<impl Stream<Item = Result<sse::Event, Infallible>>> {
+    let query = parameters.query();
+    let position = parameters.from.map(Position::from);
+
+    let events = stream
+        .subscribe(query, position)
+        .take_while(|event| future::ready(event.is_ok()))
+        .filter_map(|event| async move {
+            let event = EventResponse::from(event.ok()?);
+            let data = serde_json::to_string(&event).ok()?;
+            let event = sse::Event::default()
+                .id(event.position.to_string())
+                .data(data);
+
+            Some(Ok(event))
+        });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+// Tags
+
+async fn tags(
+    State(stream): State<AsyncStream>,
+    Parameters(parameters): Parameters<TagsParameters>,
+) -> Result<impl IntoResponse, ApiError> {
+    let prefix = parameters.prefix.unwrap_or_default();
+
+    let tags = stream
+        .tags(&prefix)
+        .await?
+        .iter()
+        .map(|tag| tag.value().to_owned())
+        .collect::<Vec<_>>();
+
+    Ok(Json(tags))
+}
+
+// Types
+
+async fn types(State(stream): State<AsyncStream>) -> Result<impl IntoResponse, ApiError> {
+    let types = stream
+        .event_types()
+        .await?
+        .iter()
+        .map(|identifier| identifier.value().to_owned())
+        .collect::<Vec<_>>();
+
+    Ok(Json(types))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Requests
+
+#[derive(Debug, Deserialize)]
+struct AppendRequest {
+    events: Vec<EventRequest>,
+    condition: Option<ConditionRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventRequest {
+    #[serde(rename = "type")]
+    identifier: String,
+    #[serde(default)]
+    version: u8,
+    #[serde(default)]
+    tags: Vec<String>,
+    data: String,
+    key_id: Option<String>,
+    signature: Option<String>,
+}
+
+impl TryFrom<EventRequest> for Event {
+    type Error = ApiError;
+
+    fn try_from(event: EventRequest) -> Result<Self, Self::Error> {
+        let data = STANDARD
+            .decode(event.data)
+            .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+
+        let decoded = event
+            .signature
+            .map(|signature| STANDARD.decode(signature))
+            .transpose()
+            .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+        let signature = signature(event.key_id, decoded.as_deref())?;
+
+        let tags = event.tags.into_iter().map(Tag::from).collect::<Vec<_>>();
+
+        let mut event = Event::new(data, (event.identifier, event.version), tags);
+
+        event.signature = signature;
+
+        Ok(event)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ConditionRequest {
+    query: Vec<SelectorRequest>,
+    after: Option<u64>,
+}
+
+impl From<ConditionRequest> for Condition {
+    fn from(condition: ConditionRequest) -> Self {
+        let query = condition
+            .query
+            .into_iter()
+            .map(Selector::from)
+            .collect::<Vec<_>>();
+
+        Condition::from((query, condition.after.map(Position::from)))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SelectorRequest {
+    #[serde(default)]
+    types: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    tag_prefixes: Vec<String>,
+    #[serde(default)]
+    tag_keys: Vec<TagKeyRequest>,
+}
+
+impl From<SelectorRequest> for Selector {
+    fn from(selector: SelectorRequest) -> Self {
+        let specifiers = selector
+            .types
+            .into_iter()
+            .map(|identifier| Specifier::from((identifier, None)))
+            .collect::<Vec<_>>();
+
+        let tags = selector.tags.into_iter().map(Tag::from).collect::<Vec<_>>();
+
+        let tag_keys = selector
+            .tag_keys
+            .into_iter()
+            .map(TagKey::from)
+            .collect::<Vec<_>>();
+
+        Selector::from((specifiers, tags, selector.tag_prefixes, tag_keys))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TagKeyRequest {
+    key: String,
+    #[serde(default)]
+    values: Vec<String>,
+}
+
+impl From<TagKeyRequest> for TagKey {
+    fn from(tag_key: TagKeyRequest) -> Self {
+        TagKey::with_values(tag_key.key, tag_key.values)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadParameters {
+    #[serde(rename = "type")]
+    types: Option<String>,
+    #[serde(rename = "tag")]
+    tags: Option<String>,
+    #[serde(rename = "tag_prefix")]
+    tag_prefixes: Option<String>,
+    #[serde(rename = "tag_key")]
+    tag_keys: Option<String>,
+    from: Option<u64>,
+    limit: Option<usize>,
+}
+
+impl ReadParameters {
+    fn query(&self) -> Query {
+        let request = SelectorRequest {
+            types: split(self.types.as_deref()),
+            tags: split(self.tags.as_deref()),
+            tag_prefixes: split(self.tag_prefixes.as_deref()),
+            tag_keys: split(self.tag_keys.as_deref())
+                .into_iter()
+                .map(|key| TagKeyRequest {
+                    key,
+                    values: Vec::new(),
+                })
+                .collect(),
+        };
+
+        Query::from(vec![Selector::from(request)])
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsParameters {
+    prefix: Option<String>,
+}
+
+fn split(values: Option<&str>) -> Vec<String> {
+    values
+        .into_iter()
+        .flat_map(|values| values.split(','))
+        .filter(|value| !value.is_empty())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Responses
+
+#[derive(Debug, Serialize)]
+struct AppendResponse {
+    position: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct EventResponse {
+    position: u64,
+    #[serde(rename = "type")]
+    identifier: String,
+    version: u8,
+    tags: Vec<String>,
+    data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archived: Option<u64>,
+    #[serde(skip_serializing_if = "is_false")]
+    redacted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    verified: Option<bool>,
+}
+
+impl From<SequencedEvent> for EventResponse {
+    fn from(event: SequencedEvent) -> Self {
+        let (identifier, version) = event.event.descriptor.take();
+        let signature = event
+            .event
+            .signature
+            .map(|(_, signature)| STANDARD.encode(signature.to_bytes()));
+
+        Self {
+            position: event.position.value(),
+            identifier: identifier.value().to_owned(),
+            version: version.value(),
+            tags: event
+                .event
+                .tags
+                .iter()
+                .map(|tag| tag.value().to_owned())
+                .collect(),
+            data: STANDARD.encode(event.event.data),
+            archived: event.archived.map(timestamp),
+            redacted: event.redacted,
+            key_id: event.provenance.key_id().map(ToOwned::to_owned),
+            signature,
+            verified: event
+                .provenance
+                .key_id()
+                .map(|_| event.provenance.is_verified()),
+        }
+    }
+}
+
+#[expect(clippy::trivially_copy_pass_by_ref)]
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::Conflict(message) => (StatusCode::CONFLICT, message),
+            Self::Forbidden(message) => (StatusCode::FORBIDDEN, message),
+            Self::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
+            Self::TooManyRequests(message) => (StatusCode::TOO_MANY_REQUESTS, message),
+        };
+
+        (status, Json(ErrorResponse { error: message })).into_response()
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        error::Error,
+        net::SocketAddr,
+    };
+
+    use esdb::{
+        Stream,
+        StreamOptions,
+    };
+    use serde_json::{
+        Value,
+        json,
+    };
+    use tokio::{
+        io::{
+            AsyncReadExt as _,
+            AsyncWriteExt as _,
+        },
+        net::{
+            TcpListener,
+            TcpStream,
+        },
+    };
+
+    use crate::serve;
+
+    async fn server() -> Result<SocketAddr, Box<dyn Error>> {
+        let stream = Stream::memory(StreamOptions::default())?;
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+
+        tokio::spawn(async move { serve(listener, stream).await.ok() });
+
+        Ok(address)
+    }
+
+    async fn request(
+        address: SocketAddr,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+    ) -> Result<(u16, Value), Box<dyn Error>> {
+        let body = body.map_or_else(String::new, Value::to_string);
+        let request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: {address}\r\nContent-Type: \
+             application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+
+        let mut connection = TcpStream::connect(address).await?;
+
+        connection.write_all(request.as_bytes()).await?;
+
+        let mut response = String::new();
+
+        connection.read_to_string(&mut response).await?;
+
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or("response has no body")?;
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .ok_or("response has no status")?
+            .parse()?;
+
+        Ok((status, serde_json::from_str(body)?))
+    }
+
+    fn append(tag: &str, after: Option<u64>) -> Value {
+        json!({
+            "events": [{ "type": "order-placed", "tags": [tag], "data": "ZGF0YQ==" }],
+            "condition": { "query": [{ "tags": [tag] }], "after": after },
+        })
+    }
+
+    #[tokio::test]
+    async fn appends_and_reads_round_trip() -> Result<(), Box<dyn Error>> {
+        let address = server().await?;
+
+        let (status, body) =
+            request(address, "POST", "/events", Some(&append("order:1", None))).await?;
+
+        assert_eq!(status, 201);
+        assert_eq!(body, json!({ "position": 0 }));
+
+        let (status, body) =
+            request(address, "POST", "/events", Some(&append("order:2", None))).await?;
+
+        assert_eq!(status, 201);
+        assert_eq!(body, json!({ "position": 1 }));
+
+        let (status, body) = request(address, "GET", "/events?tag=order:2", None).await?;
+
+        assert_eq!(status, 200);
+        assert_eq!(
+            body,
+            json!([{
+                "position": 1,
+                "type": "order-placed",
+                "version": 0,
+                "tags": ["order:2"],
+                "data": "ZGF0YQ==",
+            }])
+        );
+
+        let (status, body) = request(address, "GET", "/tags?prefix=order:", None).await?;
+
+        assert_eq!(status, 200);
+        assert_eq!(body, json!(["order:1", "order:2"]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn conflicting_appends_are_rejected() -> Result<(), Box<dyn Error>> {
+        let address = server().await?;
+
+        request(address, "POST", "/events", Some(&append("order:1", None))).await?;
+
+        let (status, body) =
+            request(address, "POST", "/events", Some(&append("order:1", None))).await?;
+
+        assert_eq!(status, 409);
+        assert!(body["error"].is_string());
+
+        let (status, _) = request(
+            address,
+            "POST",
+            "/events",
+            Some(&append("order:1", Some(0))),
+        )
+        .await?;
+
+        assert_eq!(status, 201);
+
+        let (_, body) = request(address, "GET", "/events?tag=order:1", None).await?;
+
+        assert_eq!(body.as_array().map(Vec::len), Some(2));
+
+        Ok(())
+    }
+}
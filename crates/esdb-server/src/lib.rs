@@ -0,0 +1,105 @@
+#![allow(clippy::multiple_crate_versions)]
+#![allow(clippy::missing_errors_doc)]
+#![allow(clippy::missing_panics_doc)]
+
+mod grpc;
+mod http;
+
+use std::{
+    error::Error,
+    time::{
+        SystemTime,
+        UNIX_EPOCH,
+    },
+};
+
+use axum::Router;
+use esdb::{
+    AsyncStream,
+    Conflict,
+    QuotaExceeded,
+    ReadOnly,
+    Signature,
+    Stream,
+    Unverified,
+};
+use tokio::net::TcpListener;
+
+// =================================================================================================
+// Server
+// =================================================================================================
+
+// Serve
+
+pub async fn serve(listener: TcpListener, stream: Stream) -> Result<(), Box<dyn Error>> {
+    axum::serve(listener, router(stream)).await?;
+
+    Ok(())
+}
+
+pub fn router(stream: Stream) -> Router {
+    let stream = AsyncStream::from(stream);
+
+    http::router(stream.clone()).merge(grpc::router(stream))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Timestamps
+
+fn timestamp(time: SystemTime) -> u64 {
+    let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signatures
+
+fn signature(
+    key_id: Option<String>,
+    signature: Option<&[u8]>,
+) -> Result<Option<(String, Signature)>, ApiError> {
+    match (key_id, signature) {
+        (Some(key_id), Some(signature)) => {
+            let signature = Signature::from_slice(signature)
+                .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+
+            Ok(Some((key_id, signature)))
+        }
+        (None, None) => Ok(None),
+        _ => Err(ApiError::BadRequest(
+            "key_id and signature must be given together".to_owned(),
+        )),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Errors
+
+#[derive(Debug)]
+enum ApiError {
+    BadRequest(String),
+    Conflict(String),
+    Forbidden(String),
+    Internal(String),
+    TooManyRequests(String),
+}
+
+impl From<Box<dyn Error + Send + Sync>> for ApiError {
+    fn from(err: Box<dyn Error + Send + Sync>) -> Self {
+        if err.downcast_ref::<Conflict>().is_some() {
+            Self::Conflict(err.to_string())
+        } else if err.downcast_ref::<ReadOnly>().is_some()
+            || err.downcast_ref::<Unverified>().is_some()
+        {
+            Self::Forbidden(err.to_string())
+        } else if err.downcast_ref::<QuotaExceeded>().is_some() {
+            Self::TooManyRequests(err.to_string())
+        } else {
+            Self::Internal(err.to_string())
+        }
+    }
+}
@@ -0,0 +1,66 @@
+#![allow(clippy::multiple_crate_versions)]
+
+use std::{
+    env,
+    error::Error,
+};
+
+use base64::{
+    Engine as _,
+    engine::general_purpose::STANDARD,
+};
+use esdb::{
+    Durability,
+    Stream,
+    StreamOptions,
+    VerifyingKey,
+};
+use tokio::net::TcpListener;
+
+static ADDRESS: &str = "127.0.0.1:2113";
+static PATH: &str = "./data/esdb/server";
+static PREFIX_INDEX: &str = "--prefix-index";
+static READ_ONLY: &str = "--read-only";
+static TRUSTED_KEY: &str = "--trusted-key=";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let (flags, args): (Vec<_>, Vec<_>) =
+        env::args().skip(1).partition(|arg| arg.starts_with("--"));
+
+    let prefix_index = flags.iter().any(|flag| flag == PREFIX_INDEX);
+    let read_only = flags.iter().any(|flag| flag == READ_ONLY);
+
+    let mut args = args.into_iter();
+    let path = args.next().unwrap_or_else(|| PATH.to_owned());
+    let address = args.next().unwrap_or_else(|| ADDRESS.to_owned());
+
+    let mut options = StreamOptions::default()
+        .durability(Durability::Sync)
+        .prefix_index(prefix_index)
+        .read_only(read_only);
+
+    for value in flags
+        .iter()
+        .filter_map(|flag| flag.strip_prefix(TRUSTED_KEY))
+    {
+        let (key_id, key) = trusted_key(value)?;
+
+        options = options.trusted_key(key_id, key);
+    }
+
+    let stream = Stream::open(path, options)?;
+    let listener = TcpListener::bind(address).await?;
+
+    esdb_server::serve(listener, stream).await
+}
+
+fn trusted_key(value: &str) -> Result<(&str, VerifyingKey), Box<dyn Error>> {
+    let (key_id, key) = value
+        .split_once(':')
+        .ok_or("trusted keys are given as <key id>:<base64 public key>")?;
+    let key = STANDARD.decode(key)?;
+    let key = VerifyingKey::from_bytes(key.as_slice().try_into()?)?;
+
+    Ok((key_id, key))
+}
@@ -0,0 +1,414 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs::{
+        self,
+        File,
+        OpenOptions,
+    },
+    io::{
+        BufWriter,
+        Read as _,
+        Write as _,
+    },
+    mem,
+    path::Path,
+    time::{
+        Duration,
+        UNIX_EPOCH,
+    },
+};
+
+use bytes::{
+    Buf as _,
+    BufMut as _,
+};
+use derive_more::Debug;
+use ed25519_dalek::{
+    Signature,
+    VerifyingKey,
+};
+use fancy_constructor::new;
+
+use crate::{
+    model::{
+        Descriptor,
+        Event,
+        Position,
+        Provenance,
+        SequencedEvent,
+        Stream,
+        Tag,
+    },
+    persistence,
+};
+
+// =================================================================================================
+// Archive
+// =================================================================================================
+
+pub trait Archive {
+    fn archive(&mut self, event: &SequencedEvent) -> Result<(), Box<dyn Error>>;
+
+    fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Stream Archive
+
+#[derive(new, Debug)]
+pub struct StreamArchive {
+    stream: Stream,
+    #[new(default)]
+    pending: Vec<Event>,
+}
+
+impl StreamArchive {
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+}
+
+impl Archive for StreamArchive {
+    fn archive(&mut self, event: &SequencedEvent) -> Result<(), Box<dyn Error>> {
+        let mut archived = event.event.clone();
+
+        if !event.provenance.is_verified() {
+            archived.signature = None;
+        }
+
+        self.pending.push(archived);
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+        self.stream.append(mem::take(&mut self.pending))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// File Archive
+
+#[derive(Debug)]
+pub struct FileArchive {
+    #[debug("BufWriter<File>")]
+    writer: BufWriter<File>,
+}
+
+impl FileArchive {
+    pub fn create<P>(path: P) -> Result<Self, Box<dyn Error>>
+    where
+        P: AsRef<Path>,
+    {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let mut header = Vec::with_capacity(HEADER.len() + 1);
+
+        (&mut file)
+            .take(HEADER.len() as u64 + 1)
+            .read_to_end(&mut header)?;
+
+        if !header.is_empty() && header[..] != [HEADER, &[FORMAT_VERSION]].concat() {
+            return Err(
+                "archive file is not in the current format and cannot be appended to".into(),
+            );
+        }
+
+        let mut writer = BufWriter::new(file);
+
+        if header.is_empty() {
+            writer.write_all(HEADER)?;
+            writer.write_all(&[FORMAT_VERSION])?;
+        }
+
+        Ok(Self { writer })
+    }
+
+    pub fn read<P>(
+        path: P,
+        trusted_keys: &BTreeMap<String, VerifyingKey>,
+    ) -> Result<Vec<SequencedEvent>, Box<dyn Error>>
+    where
+        P: AsRef<Path>,
+    {
+        let value = fs::read(path)?;
+        let mut value = &value[..];
+        let mut events = Vec::new();
+
+        let legacy = !value.starts_with(HEADER);
+
+        if !legacy {
+            value.advance(HEADER.len());
+
+            let version = read_bytes(&mut value, 1)?[0];
+
+            if version != FORMAT_VERSION {
+                return Err(format!("unsupported archive format version {version}").into());
+            }
+        }
+
+        while !value.is_empty() {
+            if legacy {
+                events.push(read_legacy_event(&mut value)?);
+            } else {
+                events.push(read_event(&mut value, trusted_keys)?);
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+impl Archive for FileArchive {
+    fn archive(&mut self, event: &SequencedEvent) -> Result<(), Box<dyn Error>> {
+        let mut value = Vec::new();
+
+        write_event(&mut value, event)?;
+
+        self.writer.write_all(&value)?;
+
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Box<dyn Error>> {
+        self.writer.flush()?;
+        self.writer.get_ref().sync_all()?;
+
+        Ok(())
+    }
+}
+
+// Values
+
+static ARCHIVED: u8 = 0b100;
+static FORMAT_VERSION: u8 = 2;
+static HEADER: &[u8] = b"esdb-archive";
+static REDACTED: u8 = 0b1;
+static SIGNED: u8 = 0b10;
+
+fn read_event(
+    value: &mut &[u8],
+    trusted_keys: &BTreeMap<String, VerifyingKey>,
+) -> Result<SequencedEvent, Box<dyn Error>> {
+    let position = read_u64(value)?;
+    let flags = read_bytes(value, 1)?[0];
+
+    let archived = if flags & ARCHIVED == 0 {
+        None
+    } else {
+        Some(UNIX_EPOCH + Duration::from_millis(read_u64(value)?))
+    };
+
+    let mut event = read_body(value)?;
+
+    if flags & SIGNED != 0 {
+        let key_id = read_string(value)?;
+        let signature = Signature::from_slice(&read_bytes(value, Signature::BYTE_SIZE)?)?;
+
+        event.signature = Some((key_id, signature));
+    }
+
+    let redacted = flags & REDACTED != 0;
+    let provenance = persistence::provenance(trusted_keys, &event, redacted);
+
+    Ok(SequencedEvent::new(
+        event,
+        Position::from(position),
+        archived,
+        redacted,
+        provenance,
+    ))
+}
+
+fn read_legacy_event(value: &mut &[u8]) -> Result<SequencedEvent, Box<dyn Error>> {
+    let position = read_u64(value)?;
+    let event = read_body(value)?;
+
+    Ok(SequencedEvent::new(
+        event,
+        Position::from(position),
+        None,
+        false,
+        Provenance::Unsigned,
+    ))
+}
+
+fn read_body(value: &mut &[u8]) -> Result<Event, Box<dyn Error>> {
+    let identifier = read_string(value)?;
+    let version = read_bytes(value, 1)?[0];
+    let tags_len = read_u64(value)?;
+
+    let tags = (0..tags_len)
+        .map(|_| read_string(value).map(Tag::decode))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let data_len = usize::try_from(read_u64(value)?)?;
+    let data = read_bytes(value, data_len)?;
+
+    let descriptor = Descriptor::from((identifier, version));
+
+    Ok(Event::new(data, descriptor, tags))
+}
+
+fn read_bytes(value: &mut &[u8], len: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    if value.remaining() < len {
+        return Err("truncated archive file".into());
+    }
+
+    let bytes = value[..len].to_vec();
+
+    value.advance(len);
+
+    Ok(bytes)
+}
+
+fn read_string(value: &mut &[u8]) -> Result<String, Box<dyn Error>> {
+    let len = usize::try_from(read_u64(value)?)?;
+
+    Ok(String::from_utf8(read_bytes(value, len)?)?)
+}
+
+fn read_u64(value: &mut &[u8]) -> Result<u64, Box<dyn Error>> {
+    Ok(value.try_get_u64()?)
+}
+
+fn write_event(value: &mut Vec<u8>, event: &SequencedEvent) -> Result<(), Box<dyn Error>> {
+    let descriptor = &event.event.descriptor;
+    let signature = &event.event.signature;
+
+    let mut flags = 0;
+
+    if event.redacted {
+        flags |= REDACTED;
+    }
+
+    if signature.is_some() {
+        flags |= SIGNED;
+    }
+
+    if event.archived.is_some() {
+        flags |= ARCHIVED;
+    }
+
+    value.put_u64(event.position.value());
+    value.put_u8(flags);
+
+    if let Some(archived) = event.archived {
+        let archived = archived.duration_since(UNIX_EPOCH)?.as_millis();
+
+        value.put_u64(u64::try_from(archived)?);
+    }
+
+    write_string(value, descriptor.identifier().value());
+    value.put_u8(descriptor.version().value());
+    value.put_u64(u64::try_from(event.event.tags.len())?);
+
+    for tag in &event.event.tags {
+        write_string(value, &tag.encode());
+    }
+
+    value.put_u64(u64::try_from(event.event.data.len())?);
+    value.put_slice(&event.event.data);
+
+    if let Some((key_id, signature)) = signature {
+        write_string(value, key_id);
+        value.put_slice(&signature.to_bytes());
+    }
+
+    Ok(())
+}
+
+fn write_string(value: &mut Vec<u8>, string: &str) {
+    value.put_u64(string.len() as u64);
+    value.put_slice(string.as_bytes());
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        env,
+        error::Error,
+        fs,
+        process,
+    };
+
+    use crate::{
+        archive::{
+            Archive as _,
+            FileArchive,
+        },
+        model::{
+            Event,
+            Query,
+            Stream,
+            Tag,
+        },
+        options::StreamOptions,
+    };
+
+    #[test]
+    fn file_archives_append_across_reopens() -> Result<(), Box<dyn Error>> {
+        let path = env::temp_dir().join(format!("esdb-archive-{}", process::id()));
+        let mut stream = Stream::memory(StreamOptions::default())?;
+
+        fs::remove_file(&path).ok();
+
+        stream.append([
+            Event::new(b"placed".to_vec(), ("order-placed", 0), [Tag::from(
+                "order:1",
+            )]),
+            Event::new(b"shipped".to_vec(), ("order-shipped", 0), [Tag::from(
+                "order:1",
+            )]),
+        ])?;
+
+        for event in stream.query(Query::all(), None) {
+            let mut archive = FileArchive::create(&path)?;
+
+            archive.archive(&event?)?;
+            archive.flush()?;
+        }
+
+        let events = FileArchive::read(&path, &BTreeMap::new())?;
+
+        fs::remove_file(&path)?;
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event.data, b"placed");
+        assert_eq!(events[1].event.data, b"shipped");
+
+        Ok(())
+    }
+
+    #[test]
+    fn file_archives_refuse_legacy_files() -> Result<(), Box<dyn Error>> {
+        let path = env::temp_dir().join(format!("esdb-legacy-archive-{}", process::id()));
+        let legacy = 0u64.to_be_bytes();
+
+        fs::write(&path, legacy)?;
+
+        let created = FileArchive::create(&path);
+        let contents = fs::read(&path)?;
+
+        fs::remove_file(&path)?;
+
+        assert!(created.is_err());
+        assert_eq!(contents, legacy);
+
+        Ok(())
+    }
+}
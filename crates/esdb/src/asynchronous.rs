@@ -0,0 +1,503 @@
+use std::{
+    collections::VecDeque,
+    error::Error,
+    iter,
+    path::PathBuf,
+    sync::{
+        Arc,
+        mpsc,
+    },
+    thread,
+};
+
+use derive_more::Debug;
+use fancy_constructor::new;
+use futures::stream::{
+    self,
+    Stream as FuturesStream,
+};
+use tokio::{
+    sync::{
+        oneshot,
+        watch,
+    },
+    task,
+};
+
+use crate::{
+    model::{
+        Condition,
+        Conflict,
+        Event,
+        Identifier,
+        Position,
+        Query,
+        SequencedEvent,
+        Stream,
+        Tag,
+    },
+    options::StreamOptions,
+    persistence::{
+        Corrupted,
+        MissingDatabase,
+        MissingPartition,
+        QuotaExceeded,
+        ReadOnly,
+    },
+};
+
+// =================================================================================================
+// Asynchronous
+// =================================================================================================
+
+// Configuration
+
+static GROUP_LEN: usize = 256;
+static QUERY_BATCH_LEN: usize = 256;
+
+// -------------------------------------------------------------------------------------------------
+
+// Async Stream
+
+#[derive(new, Clone, Debug)]
+#[new(vis())]
+pub struct AsyncStream {
+    #[debug("Sender")]
+    appends: mpsc::Sender<Append>,
+    #[debug("Receiver")]
+    head: watch::Receiver<Option<Position>>,
+    reader: Arc<Stream>,
+}
+
+impl AsyncStream {
+    pub async fn open<P>(path: P) -> Result<Self, Box<dyn Error + Send + Sync>>
+    where
+        P: Into<PathBuf>,
+    {
+        Self::open_with(path, StreamOptions::default()).await
+    }
+
+    pub async fn open_with<P>(
+        path: P,
+        options: StreamOptions,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>>
+    where
+        P: Into<PathBuf>,
+    {
+        let path = path.into();
+        let stream =
+            task::spawn_blocking(move || Stream::open(path, options).map_err(sendable)).await??;
+
+        Ok(Self::from(stream))
+    }
+}
+
+impl From<Stream> for AsyncStream {
+    fn from(stream: Stream) -> Self {
+        let (appends, receiver) = mpsc::channel();
+        let (head, head_receiver) = watch::channel(stream.head());
+        let reader = Arc::new(stream.reader());
+
+        thread::spawn(move || write(stream, &receiver, &head));
+
+        Self::new(appends, head_receiver, reader)
+    }
+}
+
+impl AsyncStream {
+    pub async fn append<E>(
+        &self,
+        events: E,
+    ) -> Result<Option<Position>, Box<dyn Error + Send + Sync>>
+    where
+        E: IntoIterator<Item = Event>,
+    {
+        self.append_internal(events, None).await
+    }
+
+    pub async fn append_if<E, C>(
+        &self,
+        events: E,
+        condition: C,
+    ) -> Result<Option<Position>, Box<dyn Error + Send + Sync>>
+    where
+        E: IntoIterator<Item = Event>,
+        C: Into<Condition>,
+    {
+        self.append_internal(events, Some(condition.into())).await
+    }
+
+    async fn append_internal<E>(
+        &self,
+        events: E,
+        condition: Option<Condition>,
+    ) -> Result<Option<Position>, Box<dyn Error + Send + Sync>>
+    where
+        E: IntoIterator<Item = Event>,
+    {
+        let (reply, response) = oneshot::channel();
+        let events = events.into_iter().collect();
+
+        self.appends
+            .send(Append::new(events, condition, reply))
+            .map_err(|_| "stream writer stopped")?;
+
+        response.await?
+    }
+}
+
+impl AsyncStream {
+    pub fn query<Q>(
+        &self,
+        query: Q,
+        position: Option<Position>,
+    ) -> impl FuturesStream<Item = Result<SequencedEvent, Box<dyn Error + Send + Sync>>> + Send + use<Q>
+    where
+        Q: Into<Query>,
+    {
+        let cursor = Cursor::new(self.reader.clone(), query.into(), position);
+
+        stream::unfold(cursor, |mut cursor| async move {
+            match cursor.next().await {
+                Ok(Some(event)) => Some((Ok(event), cursor)),
+                Ok(None) => None,
+                Err(err) => {
+                    cursor.done = true;
+
+                    Some((Err(err), cursor))
+                }
+            }
+        })
+    }
+
+    pub fn subscribe<Q>(
+        &self,
+        query: Q,
+        position: Option<Position>,
+    ) -> impl FuturesStream<Item = Result<SequencedEvent, Box<dyn Error + Send + Sync>>> + Send + use<Q>
+    where
+        Q: Into<Query>,
+    {
+        let cursor = Cursor::new(self.reader.clone(), query.into(), position);
+        let subscription = Some((cursor, self.head.clone()));
+
+        stream::unfold(subscription, |subscription| async move {
+            let (mut cursor, mut head) = subscription?;
+
+            loop {
+                head.borrow_and_update();
+
+                match cursor.next().await {
+                    Ok(Some(event)) => return Some((Ok(event), Some((cursor, head)))),
+                    Ok(None) => {
+                        head.changed().await.ok()?;
+
+                        cursor.done = false;
+                    }
+                    Err(err) => return Some((Err(err), None)),
+                }
+            }
+        })
+    }
+}
+
+impl AsyncStream {
+    pub fn head(&self) -> Option<Position> {
+        *self.head.borrow()
+    }
+
+    pub async fn is_empty(&self) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let reader = self.reader.clone();
+
+        task::spawn_blocking(move || reader.is_empty().map_err(sendable)).await?
+    }
+
+    pub async fn len(&self) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let reader = self.reader.clone();
+
+        task::spawn_blocking(move || reader.len().map_err(sendable)).await?
+    }
+}
+
+impl AsyncStream {
+    pub async fn event_types(&self) -> Result<Vec<Identifier>, Box<dyn Error + Send + Sync>> {
+        let reader = self.reader.clone();
+
+        task::spawn_blocking(move || reader.event_types().map_err(sendable)).await?
+    }
+
+    pub async fn tags(&self, prefix: &str) -> Result<Vec<Tag>, Box<dyn Error + Send + Sync>> {
+        let reader = self.reader.clone();
+        let prefix = prefix.to_owned();
+
+        task::spawn_blocking(move || reader.tags(&prefix).map_err(sendable)).await?
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Writer
+
+#[derive(new, Debug)]
+#[new(vis())]
+struct Append {
+    events: Vec<Event>,
+    condition: Option<Condition>,
+    #[debug("Sender")]
+    reply: oneshot::Sender<Result<Option<Position>, Box<dyn Error + Send + Sync>>>,
+}
+
+fn write(
+    mut stream: Stream,
+    appends: &mpsc::Receiver<Append>,
+    head: &watch::Sender<Option<Position>>,
+) {
+    while let Ok(append) = appends.recv() {
+        let group = iter::once(append)
+            .chain(appends.try_iter().take(GROUP_LEN - 1))
+            .filter(|append| !append.reply.is_closed())
+            .collect::<Vec<_>>();
+
+        let (requests, replies): (Vec<_>, Vec<_>) = group
+            .into_iter()
+            .map(|append| ((append.events, append.condition), append.reply))
+            .unzip();
+
+        match stream.append_group(requests) {
+            Ok(results) => {
+                head.send_replace(stream.head());
+
+                for (reply, result) in replies.into_iter().zip(results) {
+                    drop(reply.send(result));
+                }
+            }
+            Err(err) => {
+                let read_only = err.is::<ReadOnly>();
+                let err = err.to_string();
+
+                for reply in replies {
+                    let err = if read_only {
+                        ReadOnly.into()
+                    } else {
+                        err.clone().into()
+                    };
+
+                    drop(reply.send(Err(err)));
+                }
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Cursor
+
+#[derive(new, Debug)]
+#[new(vis())]
+struct Cursor {
+    reader: Arc<Stream>,
+    query: Query,
+    position: Option<Position>,
+    #[new(default)]
+    buffer: VecDeque<SequencedEvent>,
+    #[new(default)]
+    done: bool,
+}
+
+impl Cursor {
+    async fn next(&mut self) -> Result<Option<SequencedEvent>, Box<dyn Error + Send + Sync>> {
+        if self.buffer.is_empty() && !self.done {
+            let reader = self.reader.clone();
+            let query = self.query.clone();
+            let position = self.position;
+
+            let events = task::spawn_blocking(move || {
+                reader
+                    .query(query, position)
+                    .take(QUERY_BATCH_LEN)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(sendable)
+            })
+            .await??;
+
+            self.done = events.len() < QUERY_BATCH_LEN;
+            self.buffer.extend(events);
+        }
+
+        let event = self.buffer.pop_front();
+
+        if let Some(event) = &event {
+            self.position = Some(Position::from(event.position.value() + 1));
+        }
+
+        Ok(event)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Errors
+
+fn sendable(err: Box<dyn Error>) -> Box<dyn Error + Send + Sync> {
+    let err = match err.downcast::<Conflict>() {
+        Ok(conflict) => return conflict,
+        Err(err) => err,
+    };
+
+    let err = match err.downcast::<ReadOnly>() {
+        Ok(read_only) => return read_only,
+        Err(err) => err,
+    };
+
+    let err = match err.downcast::<QuotaExceeded>() {
+        Ok(exceeded) => return exceeded,
+        Err(err) => err,
+    };
+
+    let err = match err.downcast::<Corrupted>() {
+        Ok(corrupted) => return corrupted,
+        Err(err) => err,
+    };
+
+    let err = match err.downcast::<MissingDatabase>() {
+        Ok(missing) => return missing,
+        Err(err) => err,
+    };
+
+    let err = match err.downcast::<MissingPartition>() {
+        Ok(missing) => return missing,
+        Err(err) => err,
+    };
+
+    match err.downcast::<fjall::Error>() {
+        Ok(err) => err,
+        Err(err) => err.to_string().into(),
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use futures::{
+        StreamExt as _,
+        future,
+    };
+
+    use crate::{
+        asynchronous::AsyncStream,
+        model::{
+            Condition,
+            Conflict,
+            Event,
+            Position,
+            Query,
+            Selector,
+            Specifier,
+            Stream,
+            Tag,
+            TagKey,
+        },
+        options::StreamOptions,
+    };
+
+    fn stream() -> Result<AsyncStream, Box<dyn Error + Send + Sync>> {
+        let stream = Stream::memory(StreamOptions::default()).map_err(|err| err.to_string())?;
+
+        Ok(AsyncStream::from(stream))
+    }
+
+    fn event(tag: &str) -> Event {
+        Event::new(b"data".to_vec(), ("order-placed", 0), [Tag::from(tag)])
+    }
+
+    fn query(tag: &str) -> Query {
+        let selector = Selector::from((
+            Vec::<Specifier>::new(),
+            [Tag::from(tag)],
+            Vec::<String>::new(),
+            Vec::<TagKey>::new(),
+        ));
+
+        Query::from([selector])
+    }
+
+    #[tokio::test]
+    async fn subscriptions_wake_on_append() -> Result<(), Box<dyn Error + Send + Sync>> {
+        let stream = stream()?;
+        let subscription = stream.subscribe(query("order:2"), None);
+        let subscriber = tokio::spawn(
+            subscription
+                .take(2)
+                .map(|event| event.map(|event| event.position))
+                .collect::<Vec<_>>(),
+        );
+
+        for tag in ["order:1", "order:2", "order:1", "order:2"] {
+            stream.append([event(tag)]).await?;
+        }
+
+        let positions = subscriber
+            .await?
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(positions, [Position::from(1_u64), Position::from(3_u64)]);
+        assert_eq!(stream.head(), Some(Position::from(3_u64)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn grouped_appends_keep_request_order() -> Result<(), Box<dyn Error + Send + Sync>> {
+        let stream = stream()?;
+        let tags = (0..100).map(|i| format!("order:{i}")).collect::<Vec<_>>();
+
+        let appends = tags.iter().map(|tag| stream.append([event(tag)]));
+        let positions = future::try_join_all(appends).await?;
+
+        assert_eq!(
+            positions,
+            (0..100_u64)
+                .map(|i| Some(Position::from(i)))
+                .collect::<Vec<_>>()
+        );
+
+        for (position, tag) in tags.iter().enumerate() {
+            let events = stream
+                .query(query(tag), None)
+                .map(|event| event.map(|event| event.position.value()))
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+
+            assert_eq!(events, [u64::try_from(position)?]);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn grouped_conditions_see_earlier_appends() -> Result<(), Box<dyn Error + Send + Sync>> {
+        let stream = stream()?;
+        let condition = || Condition::from((query("order:1"), None));
+
+        let (first, second) = future::join(
+            stream.append_if([event("order:1")], condition()),
+            stream.append_if([event("order:1")], condition()),
+        )
+        .await;
+
+        assert_eq!(first?, Some(Position::from(0_u64)));
+        assert!(second.is_err_and(|err| err.is::<Conflict>()));
+        assert_eq!(stream.len().await?, 1);
+
+        Ok(())
+    }
+}
@@ -1,14 +1,92 @@
 #![allow(clippy::multiple_crate_versions)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::missing_panics_doc)]
+#![allow(clippy::must_use_candidate)]
 
+mod archive;
+#[cfg(feature = "async")]
+mod asynchronous;
 mod model;
+mod options;
 pub mod persistence;
+mod policy;
 
+pub use ed25519_dalek::{
+    Signature,
+    SigningKey,
+    VerifyingKey,
+};
+
+#[cfg(feature = "async")]
+pub use self::asynchronous::AsyncStream;
 pub use self::{
+    archive::{
+        Archive,
+        FileArchive,
+        StreamArchive,
+    },
     model::{
+        Appended,
+        AuditEntry,
+        Checkpoint,
+        Condition,
+        Conflict,
+        Descriptor,
         Event,
+        Identifier,
+        IndexKey,
+        IndexRange,
+        Position,
+        Provenance,
+        Query,
+        Redaction,
+        Selector,
+        SequencedEvent,
+        Specifier,
+        Stats,
         Stream,
+        Tag,
+        TagKey,
+        Usage,
+        Verification,
+        Version,
+    },
+    options::{
+        Compression,
+        DatabaseOptions,
+        Durability,
+        Encoding,
+        Extractor,
+        PartitionOptions,
+        Quota,
+        QuotaLimit,
+        Retention,
+        StreamOptions,
+    },
+    persistence::{
+        Corrupted,
+        Database,
+        MissingDatabase,
+        MissingPartition,
+        QuotaExceeded,
+        ReadOnly,
+        StreamOpen,
+        Tampered,
+        Unverified,
+        backend::{
+            Backend,
+            FjallBackend,
+            MemoryBackend,
+            Operation,
+            Partition,
+        },
+        blob::Payload,
+    },
+    policy::{
+        Access,
+        Denied,
+        PolicyStream,
+        Principal,
+        Rule,
     },
-    persistence::Database,
 };
@@ -1,18 +1,58 @@
 use std::{
+    borrow::Cow,
+    collections::BTreeMap,
     error::Error,
-    ops::Range,
+    ops::{
+        Bound,
+        Range,
+        RangeBounds,
+    },
     path::Path,
+    sync::Arc,
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
 };
 
-use derive_more::Debug;
+use bytes::{
+    Buf as _,
+    BufMut as _,
+};
+use derive_more::{
+    Debug,
+    Display,
+    Error,
+};
+use ed25519_dalek::{
+    Signature,
+    Signer as _,
+    SigningKey,
+    VerifyingKey,
+};
 use fancy_constructor::new;
 
-use crate::persistence::{
-    self,
-    Database,
-    Partitions,
-    Read,
-    Write,
+use crate::{
+    archive::Archive,
+    options::{
+        Retention,
+        StreamOptions,
+    },
+    persistence::{
+        self,
+        Database,
+        HashedEvent,
+        Hasher,
+        Lease,
+        Partitions,
+        Read,
+        ReadOnly,
+        Tampered,
+        Write,
+        backend::Backend,
+        blob::Payload,
+    },
 };
 
 // =================================================================================================
@@ -21,7 +61,7 @@ use crate::persistence::{
 
 // Event
 
-#[derive(new, Debug)]
+#[derive(new, Clone, Debug)]
 pub struct Event {
     #[new(into)]
     pub data: Vec<u8>,
@@ -29,18 +69,75 @@ pub struct Event {
     pub descriptor: Descriptor,
     #[new(into)]
     pub tags: Vec<Tag>,
+    #[new(default)]
+    pub signature: Option<(String, Signature)>,
+}
+
+impl Event {
+    pub fn sign<K>(&mut self, key_id: K, key: &SigningKey) -> Result<(), Box<dyn Error>>
+    where
+        K: Into<String>,
+    {
+        persistence::sign(self, key_id.into(), key)
+    }
+}
+
+// Sequenced Event
+
+#[derive(new, Debug)]
+#[new(vis(pub(crate)))]
+pub struct SequencedEvent {
+    pub event: Event,
+    pub position: Position,
+    pub archived: Option<SystemTime>,
+    pub redacted: bool,
+    pub provenance: Provenance,
+}
+
+// Provenance
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Provenance {
+    Unsigned,
+    Verified(String),
+    Untrusted(String),
+    Invalid(String),
+    Redacted(String),
+}
+
+impl Provenance {
+    pub fn key_id(&self) -> Option<&str> {
+        match self {
+            Self::Unsigned => None,
+            Self::Verified(key_id)
+            | Self::Untrusted(key_id)
+            | Self::Invalid(key_id)
+            | Self::Redacted(key_id) => Some(key_id),
+        }
+    }
+
+    pub fn is_verified(&self) -> bool {
+        matches!(self, Self::Verified(_))
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
 
 // Stream
 
+static ARCHIVE_BATCH_LEN: usize = 1024;
+
 #[derive(new, Debug)]
 #[new(name(new_internal), vis())]
 pub struct Stream {
     database: Database,
+    hasher: Hasher,
     partitions: Partitions,
     position: Position,
+    #[cfg_attr(not(feature = "async"), expect(dead_code))]
+    lease: Option<Arc<Lease>>,
+    #[new(default)]
+    rates: BTreeMap<String, (u64, u64)>,
 }
 
 impl Stream {
@@ -48,13 +145,66 @@ impl Stream {
     where
         P: AsRef<Path>,
     {
-        let database = persistence::database(path)?;
-        let partitions = persistence::partitions(&database)?;
+        Self::open(path, StreamOptions::default())
+    }
+
+    pub fn open<P>(path: P, options: StreamOptions) -> Result<Self, Box<dyn Error>>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_database(Database::open(path, options)?, None)
+    }
+
+    pub fn memory(options: StreamOptions) -> Result<Self, Box<dyn Error>> {
+        Self::from_database(Database::memory(options), None)
+    }
+
+    pub fn with_backend<B>(backend: B, options: StreamOptions) -> Result<Self, Box<dyn Error>>
+    where
+        B: Backend + 'static,
+    {
+        Self::from_database(Database::with_backend(backend, options), None)
+    }
+
+    pub(crate) fn from_database(
+        database: Database,
+        name: Option<&str>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let lease = name.map(|name| database.lease(name)).transpose()?;
+        let mut partitions = persistence::partitions(&database, name)?;
+
+        persistence::encoding(&database, &mut partitions)?;
+
+        let hasher = persistence::hasher(&database, &partitions)?;
+
+        persistence::format(&database, &mut partitions)?;
+        persistence::checksum(&database, &mut partitions)?;
+        persistence::rechain(&database, &partitions)?;
+        persistence::recount(&database, &partitions)?;
+        persistence::measure(&database, &partitions)?;
+        persistence::reindex(&database, &mut partitions, hasher)?;
 
         let len = persistence::data::len(&Read::new(&partitions))?;
         let position = len.into();
 
-        Ok(Self::new_internal(database, partitions, position))
+        Ok(Self::new_internal(
+            database,
+            hasher,
+            partitions,
+            position,
+            lease.map(Arc::new),
+        ))
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn reader(&self) -> Self {
+        Self::new_internal(
+            self.database.clone(),
+            self.hasher,
+            self.partitions.clone(),
+            self.position,
+            self.lease.clone(),
+        )
     }
 }
 
@@ -63,25 +213,363 @@ impl Stream {
     where
         E: IntoIterator<Item = Event>,
     {
-        let mut batch = self.database.as_ref().batch();
+        self.append_internal(events, None)
+    }
+
+    pub fn append_if<E, C>(&mut self, events: E, condition: C) -> Result<(), Box<dyn Error>>
+    where
+        E: IntoIterator<Item = Event>,
+        C: Into<Condition>,
+    {
+        self.append_internal(events, Some(condition.into()))
+    }
+
+    fn append_internal<E>(
+        &mut self,
+        events: E,
+        condition: Option<Condition>,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        E: IntoIterator<Item = Event>,
+    {
+        let events = events.into_iter().collect();
+        let mut results = self.append_group([(events, condition)])?;
+
+        match results.pop() {
+            Some(Err(err)) => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn append_group<A>(&mut self, appends: A) -> Result<Vec<Appended>, Box<dyn Error>>
+    where
+        A: IntoIterator<Item = (Vec<Event>, Option<Condition>)>,
+    {
+        let options = self.database.options();
+
+        if options.read_only {
+            return Err(ReadOnly.into());
+        }
+
+        let mut batch = self.database.batch().durability(options.durability);
+        let mut position = self.position;
+        let mut pending = Vec::new();
+        let mut results = Vec::new();
 
         {
             let mut write = Write::new(&mut batch, &self.partitions);
 
-            for event in events {
-                persistence::insert(&mut write, self.position, event);
+            for (events, condition) in appends {
+                if let Some(condition) = condition
+                    && let Some(conflict) = self.check(condition, &write, &pending)?
+                {
+                    results.push(Err(conflict.into()));
+
+                    continue;
+                }
+
+                let events = match self.prepare(events) {
+                    Ok(events) => events,
+                    Err(err) => {
+                        results.push(Err(err));
+
+                        continue;
+                    }
+                };
+
+                if let Some(exceeded) =
+                    persistence::limit(&write, &events, &options.quotas, &self.rates)?
+                {
+                    results.push(Err(exceeded.into()));
+
+                    continue;
+                }
+
+                for mut event in events {
+                    persistence::assign(&mut write, &mut event)?;
+                    persistence::insert(&mut write, position, &event)?;
+                    pending.push((position, event));
+
+                    position.increment();
+                }
 
-                self.position.increment();
+                results.push(Ok(position.value().checked_sub(1).map(Position::from)));
             }
+
+            persistence::flush(&mut write)?;
+        }
+
+        batch.commit()?;
+
+        persistence::meter(&mut self.rates, &options.quotas, &pending);
+
+        self.position = position;
+
+        Ok(results)
+    }
+
+    fn prepare(
+        &self,
+        events: Vec<Event>,
+    ) -> Result<Vec<HashedEvent>, Box<dyn Error + Send + Sync>> {
+        let options = self.database.options();
+        let read = Read::new(&self.partitions);
+
+        events
+            .into_iter()
+            .map(|event| {
+                persistence::validate_event(&event)?;
+                persistence::authenticate(&read, &event)?;
+
+                Ok(self.hasher.event(event, &options.extractors))
+            })
+            .collect()
+    }
+
+    fn check(
+        &self,
+        condition: Condition,
+        write: &Write<'_>,
+        pending: &[(Position, HashedEvent)],
+    ) -> Result<Option<Conflict>, Box<dyn Error>> {
+        let (query, position) = condition.take();
+        let mut query = self.hasher.query(query);
+        let position = position.map(|mut position| {
+            position.increment();
+            position
+        });
+
+        let read = Read::new(&self.partitions);
+
+        persistence::resolve_query(&read, Some(write), &mut query)?;
+
+        if let Some(conflict) = persistence::positions(&read, position, &query)?.next() {
+            return Ok(Some(Conflict::new(conflict.into())));
+        }
+
+        let conflict = pending
+            .iter()
+            .filter(|(pending, _)| position.is_none_or(|position| *pending >= position))
+            .find(|(_, event)| query.matches(event))
+            .map(|(pending, _)| Conflict::new(*pending));
+
+        Ok(conflict)
+    }
+}
+
+impl Stream {
+    pub fn archive<A>(
+        &mut self,
+        retention: &Retention,
+        archive: &mut A,
+    ) -> Result<u64, Box<dyn Error>>
+    where
+        A: Archive,
+    {
+        let options = self.database.options();
+
+        if options.read_only {
+            return Err(ReadOnly.into());
+        }
+
+        let read = Read::new(&self.partitions);
+        let expired = persistence::expired(&read, self.hasher, retention)?;
+        let archived = persistence::timestamp();
+        let archived_at = UNIX_EPOCH + Duration::from_millis(archived);
+
+        for positions in expired.chunks(ARCHIVE_BATCH_LEN) {
+            let mut batch = self.database.batch().durability(options.durability);
+
+            {
+                let mut write = Write::new(&mut batch, &self.partitions);
+
+                for position in positions {
+                    let position = Position::from(*position);
+                    let mut event = persistence::get(&read, position)?
+                        .ok_or("expired event missing from data")?;
+
+                    event.archived = Some(archived_at);
+
+                    archive.archive(&event)?;
+
+                    let mut event = self.hasher.event(event.event, &options.extractors);
+
+                    persistence::resolve(&read, &mut event)?;
+                    persistence::remove(&mut write, position, &event, archived)?;
+                }
+
+                persistence::flush(&mut write)?;
+            }
+
+            archive.flush()?;
+            batch.commit()?;
+        }
+
+        Ok(expired.len() as u64)
+    }
+}
+
+impl Stream {
+    pub fn redact(
+        &mut self,
+        position: Position,
+        redaction: &Redaction,
+    ) -> Result<(), Box<dyn Error>> {
+        let options = self.database.options();
+
+        if options.read_only {
+            return Err(ReadOnly.into());
+        }
+
+        let read = Read::new(&self.partitions);
+        let event = persistence::get(&read, position)?
+            .ok_or_else(|| format!("no event at position {}", position.value()))?;
+
+        if event.archived.is_some() {
+            return Err("archived events cannot be redacted".into());
+        }
+
+        let replacement = Event::new(
+            redaction.data.clone(),
+            event.event.descriptor.clone(),
+            event.event.tags.clone(),
+        );
+
+        let mut event = self.hasher.event(event.event, &options.extractors);
+        let mut replacement = self.hasher.event(replacement, &options.extractors);
+
+        persistence::resolve(&read, &mut event)?;
+        persistence::resolve(&read, &mut replacement)?;
+
+        let mut batch = self.database.batch().durability(options.durability);
+
+        {
+            let mut write = Write::new(&mut batch, &self.partitions);
+
+            persistence::redact(&mut write, position, &event, &replacement, redaction)?;
         }
 
         batch.commit()?;
 
         Ok(())
     }
+
+    pub fn audit(&self) -> Result<Vec<AuditEntry>, Box<dyn Error>> {
+        persistence::audit(&Read::new(&self.partitions))
+    }
 }
 
 impl Stream {
+    pub fn verify_chain<R>(&self, range: R) -> Result<Verification, Box<dyn Error>>
+    where
+        R: RangeBounds<Position>,
+    {
+        let len = self.len()?;
+
+        let start = match range.start_bound() {
+            Bound::Included(position) => Some(position.value()),
+            Bound::Excluded(position) => position.value().checked_add(1),
+            Bound::Unbounded => Some(0),
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(position) => position.value().checked_add(1),
+            Bound::Excluded(position) => Some(position.value()),
+            Bound::Unbounded => Some(len),
+        };
+
+        let (start, end) = start
+            .zip(end)
+            .filter(|(start, end)| start <= end && *end <= len)
+            .ok_or_else(|| format!("invalid chain range for {len} events"))?;
+
+        persistence::verify_chain(
+            &Read::new(&self.partitions),
+            Position::from(start),
+            Position::from(end),
+        )
+    }
+
+    pub fn checkpoint(&self, key: &SigningKey) -> Result<Checkpoint, Box<dyn Error>> {
+        let position = self
+            .len()?
+            .checked_sub(1)
+            .map(Position::from)
+            .ok_or("cannot checkpoint an empty stream")?;
+
+        let read = Read::new(&self.partitions);
+        let hash = persistence::chain_hash(&read, position)?;
+        let amendments = persistence::amendment_head(&read)?;
+
+        Ok(Checkpoint::sign(
+            position,
+            hash,
+            amendments,
+            persistence::timestamp(),
+            key,
+        ))
+    }
+
+    pub fn verify_checkpoint(
+        &self,
+        checkpoint: &Checkpoint,
+        key: &VerifyingKey,
+    ) -> Result<Verification, Box<dyn Error>> {
+        checkpoint.verify(key)?;
+
+        let verification = self.verify_chain(..=checkpoint.position())?;
+
+        if verification.hash() != checkpoint.hash() {
+            return Err(Tampered::new(checkpoint.position()).into());
+        }
+
+        let read = Read::new(&self.partitions);
+        let (amendments, _) = persistence::amendment_head(&read)?;
+
+        if amendments < checkpoint.amendments()
+            || persistence::amendment_hash(&read, checkpoint.amendments())?
+                != *checkpoint.amendment_hash()
+        {
+            return Err(Tampered::new(checkpoint.position()).into());
+        }
+
+        Ok(verification)
+    }
+}
+
+impl Stream {
+    pub fn backup<P>(&self, path: P) -> Result<(), Box<dyn Error>>
+    where
+        P: AsRef<Path>,
+    {
+        self.database.backup(path)
+    }
+}
+
+impl Stream {
+    pub fn query<Q>(
+        &self,
+        query: Q,
+        position: Option<Position>,
+    ) -> impl Iterator<Item = Result<SequencedEvent, Box<dyn Error>>>
+    where
+        Q: Into<Query>,
+    {
+        let query = self.hasher.query(query.into());
+
+        persistence::query(Read::new(&self.partitions), position, query)
+    }
+
+    pub fn payload(&self, position: Position) -> Result<Option<Payload>, Box<dyn Error>> {
+        persistence::payload(&Read::new(&self.partitions), position)
+    }
+}
+
+impl Stream {
+    pub fn head(&self) -> Option<Position> {
+        self.position.value().checked_sub(1).map(Position::from)
+    }
+
     pub fn is_empty(&self) -> Result<bool, Box<dyn Error>> {
         persistence::data::is_empty(&Read::new(&self.partitions))
     }
@@ -89,6 +577,317 @@ impl Stream {
     pub fn len(&self) -> Result<u64, Box<dyn Error>> {
         persistence::data::len(&Read::new(&self.partitions))
     }
+
+    pub fn stats(&self) -> Result<Stats, Box<dyn Error>> {
+        persistence::stats(&Read::new(&self.partitions), &self.rates)
+    }
+}
+
+impl Stream {
+    pub fn event_types(&self) -> Result<Vec<Identifier>, Box<dyn Error>> {
+        persistence::reference::identifiers(&Read::new(&self.partitions), "")
+    }
+
+    pub fn tags(&self, prefix: &str) -> Result<Vec<Tag>, Box<dyn Error>> {
+        persistence::reference::tags(&Read::new(&self.partitions), prefix)
+    }
+}
+
+// Stats
+
+#[derive(new, Debug)]
+#[new(vis(pub(crate)))]
+pub struct Stats {
+    events: u64,
+    distinct_identifiers: u64,
+    distinct_tags: u64,
+    identifiers: BTreeMap<Identifier, u64>,
+    tags: BTreeMap<Tag, u64>,
+    partitions: BTreeMap<String, u64>,
+    usage: BTreeMap<String, Usage>,
+}
+
+impl Stats {
+    pub fn events(&self) -> u64 {
+        self.events
+    }
+
+    pub fn distinct_identifiers(&self) -> u64 {
+        self.distinct_identifiers
+    }
+
+    pub fn distinct_tags(&self) -> u64 {
+        self.distinct_tags
+    }
+
+    pub fn identifiers(&self) -> &BTreeMap<Identifier, u64> {
+        &self.identifiers
+    }
+
+    pub fn tags(&self) -> &BTreeMap<Tag, u64> {
+        &self.tags
+    }
+
+    pub fn partitions(&self) -> &BTreeMap<String, u64> {
+        &self.partitions
+    }
+
+    pub fn usage(&self) -> &BTreeMap<String, Usage> {
+        &self.usage
+    }
+}
+
+// Usage
+
+#[derive(new, Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[new(vis(pub(crate)))]
+pub struct Usage {
+    storage_bytes: u64,
+    daily_bytes: u64,
+    events_per_second: u64,
+}
+
+impl Usage {
+    pub fn storage_bytes(&self) -> u64 {
+        self.storage_bytes
+    }
+
+    pub fn daily_bytes(&self) -> u64 {
+        self.daily_bytes
+    }
+
+    pub fn events_per_second(&self) -> u64 {
+        self.events_per_second
+    }
+}
+
+// Conflict
+
+pub type Appended = Result<Option<Position>, Box<dyn Error + Send + Sync>>;
+
+#[derive(new, Debug, Display, Error)]
+#[display("append condition failed: event at position {} matches", position.value())]
+#[new(vis())]
+pub struct Conflict {
+    position: Position,
+}
+
+impl Conflict {
+    pub fn position(&self) -> Position {
+        self.position
+    }
+}
+
+// Redaction
+
+#[derive(new, Clone, Debug)]
+pub struct Redaction {
+    #[new(into)]
+    data: Vec<u8>,
+    #[new(into)]
+    actor: String,
+    #[new(into)]
+    reason: String,
+}
+
+impl Redaction {
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+// Audit Entry
+
+#[derive(new, Clone, Debug)]
+#[new(vis(pub(crate)))]
+pub struct AuditEntry {
+    position: Position,
+    time: SystemTime,
+    actor: String,
+    reason: String,
+}
+
+impl AuditEntry {
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+// Verification
+
+#[derive(new, Clone, Debug)]
+#[new(vis(pub(crate)))]
+pub struct Verification {
+    hash: [u8; 32],
+    redacted: Vec<Position>,
+    archived: Vec<Position>,
+}
+
+impl Verification {
+    pub fn hash(&self) -> &[u8; 32] {
+        &self.hash
+    }
+
+    pub fn redacted(&self) -> &[Position] {
+        &self.redacted
+    }
+
+    pub fn archived(&self) -> &[Position] {
+        &self.archived
+    }
+}
+
+// Checkpoint
+
+static CHECKPOINT_CONTEXT: &[u8] = b"esdb-checkpoint";
+static CHECKPOINT_LEN: usize = MESSAGE_LEN + Signature::BYTE_SIZE;
+static MESSAGE_LEN: usize = size_of::<u64>() + 32 + size_of::<u64>() + 32 + size_of::<u64>();
+
+#[derive(new, Clone, Debug, Eq, PartialEq)]
+#[new(vis())]
+pub struct Checkpoint {
+    position: Position,
+    hash: [u8; 32],
+    amendments: u64,
+    amendment_hash: [u8; 32],
+    timestamp: u64,
+    signature: Signature,
+}
+
+impl Checkpoint {
+    fn sign(
+        position: Position,
+        hash: [u8; 32],
+        (amendments, amendment_hash): (u64, [u8; 32]),
+        timestamp: u64,
+        key: &SigningKey,
+    ) -> Self {
+        let message = message(position, &hash, amendments, &amendment_hash, timestamp);
+        let signature = key.sign(&message);
+
+        Self::new(
+            position,
+            hash,
+            amendments,
+            amendment_hash,
+            timestamp,
+            signature,
+        )
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    pub fn hash(&self) -> &[u8; 32] {
+        &self.hash
+    }
+
+    pub fn amendments(&self) -> u64 {
+        self.amendments
+    }
+
+    pub fn amendment_hash(&self) -> &[u8; 32] {
+        &self.amendment_hash
+    }
+
+    pub fn time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.timestamp)
+    }
+
+    pub fn verify(&self, key: &VerifyingKey) -> Result<(), Box<dyn Error>> {
+        let message = message(
+            self.position,
+            &self.hash,
+            self.amendments,
+            &self.amendment_hash,
+            self.timestamp,
+        );
+
+        key.verify_strict(&message, &self.signature)?;
+
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(CHECKPOINT_LEN);
+
+        bytes.put_u64(self.position.value());
+        bytes.put_slice(&self.hash);
+        bytes.put_u64(self.amendments);
+        bytes.put_slice(&self.amendment_hash);
+        bytes.put_u64(self.timestamp);
+        bytes.put_slice(&self.signature.to_bytes());
+
+        bytes
+    }
+
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        if bytes.len() != CHECKPOINT_LEN {
+            return Err(format!("invalid checkpoint length {}", bytes.len()).into());
+        }
+
+        let position = Position::from(bytes.get_u64());
+        let hash = bytes[..32].try_into()?;
+
+        bytes.advance(32);
+
+        let amendments = bytes.get_u64();
+        let amendment_hash = bytes[..32].try_into()?;
+
+        bytes.advance(32);
+
+        let timestamp = bytes.get_u64();
+        let signature = Signature::from_slice(bytes)?;
+
+        Ok(Self::new(
+            position,
+            hash,
+            amendments,
+            amendment_hash,
+            timestamp,
+            signature,
+        ))
+    }
+}
+
+fn message(
+    position: Position,
+    hash: &[u8; 32],
+    amendments: u64,
+    amendment_hash: &[u8; 32],
+    timestamp: u64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(CHECKPOINT_CONTEXT.len() + MESSAGE_LEN);
+
+    message.put_slice(CHECKPOINT_CONTEXT);
+    message.put_u64(position.value());
+    message.put_slice(hash);
+    message.put_u64(amendments);
+    message.put_slice(amendment_hash);
+    message.put_u64(timestamp);
+
+    message
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -230,21 +1029,348 @@ where
 
 // Tag
 
+static KEY_SEPARATOR: char = '\0';
+static TAG_SEPARATOR: char = ':';
+
 #[derive(new, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 #[new(vis())]
-pub struct Tag(#[new(into)] String);
+pub struct Tag(#[new(into)] String, Option<usize>);
 
 impl Tag {
+    pub fn keyed<K, V>(key: K, value: V) -> Self
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let key = key.as_ref();
+        let value = format!("{key}{TAG_SEPARATOR}{}", value.as_ref());
+
+        Self::new(value, Some(key.len()))
+    }
+
+    pub fn key(&self) -> Option<&str> {
+        self.parts().map(|(key, _)| key)
+    }
+
+    pub fn parts(&self) -> Option<(&str, &str)> {
+        self.1
+            .map(|len| (&self.0[..len], &self.0[len + TAG_SEPARATOR.len_utf8()..]))
+    }
+
     pub fn value(&self) -> &str {
         &self.0
     }
 }
 
+impl Tag {
+    pub(crate) fn decode(value: String) -> Self {
+        match value.split_once(KEY_SEPARATOR) {
+            Some((key, value)) => Self::keyed(key, value),
+            None => Self::new(value, None),
+        }
+    }
+
+    pub(crate) fn encode(&self) -> Cow<'_, str> {
+        match self.parts() {
+            Some((key, value)) => Cow::Owned(format!("{key}{KEY_SEPARATOR}{value}")),
+            None => Cow::Borrowed(&self.0),
+        }
+    }
+
+    pub(crate) fn is_encodable(&self) -> bool {
+        !self.key().unwrap_or(&self.0).contains(KEY_SEPARATOR)
+    }
+}
+
 impl<T> From<T> for Tag
 where
     T: Into<String>,
 {
     fn from(value: T) -> Self {
+        Self::new(value, None)
+    }
+}
+
+// Tag Key
+
+#[derive(new, Clone, Debug, Eq, PartialEq)]
+#[new(vis())]
+pub struct TagKey(#[new(into)] String, #[new(into)] Vec<String>);
+
+impl TagKey {
+    pub fn with_values<K, V>(key: K, values: V) -> Self
+    where
+        K: Into<String>,
+        V: IntoIterator,
+        V::Item: Into<String>,
+    {
+        Self::new(key, values.into_iter().map(Into::into).collect::<Vec<_>>())
+    }
+
+    pub fn key(&self) -> &str {
+        &self.0
+    }
+
+    pub fn values(&self) -> &[String] {
+        &self.1
+    }
+
+    pub fn tags(&self) -> impl Iterator<Item = Tag> + use<'_> {
+        self.values()
+            .iter()
+            .map(|value| Tag::keyed(self.key(), value))
+    }
+}
+
+impl<T> From<T> for TagKey
+where
+    T: Into<String>,
+{
+    fn from(value: T) -> Self {
+        Self::new(value, Vec::new())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Index
+
+// Index Key
+
+#[derive(new, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[new(vis())]
+pub struct IndexKey(#[new(into)] Vec<u8>);
+
+impl IndexKey {
+    pub fn value(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<&str> for IndexKey {
+    fn from(value: &str) -> Self {
+        Self::new(value.as_bytes())
+    }
+}
+
+impl From<String> for IndexKey {
+    fn from(value: String) -> Self {
+        Self::new(value.into_bytes())
+    }
+}
+
+impl From<Vec<u8>> for IndexKey {
+    fn from(value: Vec<u8>) -> Self {
         Self::new(value)
     }
 }
+
+impl From<i64> for IndexKey {
+    fn from(value: i64) -> Self {
+        Self::new((value.cast_unsigned() ^ (1 << 63)).to_be_bytes())
+    }
+}
+
+impl From<u64> for IndexKey {
+    fn from(value: u64) -> Self {
+        Self::new(value.to_be_bytes())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Query
+
+// Query
+
+#[derive(new, Clone, Debug, Eq, PartialEq)]
+#[new(vis())]
+pub struct Query(#[new(into)] Vec<Selector>);
+
+impl Query {
+    pub fn all() -> Self {
+        Self::new(vec![Selector::default()])
+    }
+
+    pub fn selectors(&self) -> &[Selector] {
+        &self.0
+    }
+
+    pub fn take(self) -> Vec<Selector> {
+        self.0
+    }
+}
+
+impl<T> From<T> for Query
+where
+    T: Into<Vec<Selector>>,
+{
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+// Selector
+
+type SelectorParts = (
+    Vec<Specifier>,
+    Vec<Tag>,
+    Vec<String>,
+    Vec<TagKey>,
+    Vec<IndexRange>,
+);
+
+#[derive(new, Clone, Debug, Default, Eq, PartialEq)]
+#[new(vis())]
+pub struct Selector(
+    #[new(into)] Vec<Specifier>,
+    #[new(into)] Vec<Tag>,
+    #[new(into)] Vec<String>,
+    #[new(into)] Vec<TagKey>,
+    #[new(into)] Vec<IndexRange>,
+);
+
+impl Selector {
+    pub fn specifiers(&self) -> &[Specifier] {
+        &self.0
+    }
+
+    pub fn tags(&self) -> &[Tag] {
+        &self.1
+    }
+
+    pub fn tag_prefixes(&self) -> &[String] {
+        &self.2
+    }
+
+    pub fn tag_keys(&self) -> &[TagKey] {
+        &self.3
+    }
+
+    pub fn index_ranges(&self) -> &[IndexRange] {
+        &self.4
+    }
+
+    pub fn take(self) -> SelectorParts {
+        (self.0, self.1, self.2, self.3, self.4)
+    }
+}
+
+impl<T, U> From<(T, U)> for Selector
+where
+    T: Into<Vec<Specifier>>,
+    U: Into<Vec<Tag>>,
+{
+    fn from(value: (T, U)) -> Self {
+        Self::new(value.0, value.1, Vec::new(), Vec::new(), Vec::new())
+    }
+}
+
+impl<T, U, V> From<(T, U, V)> for Selector
+where
+    T: Into<Vec<Specifier>>,
+    U: Into<Vec<Tag>>,
+    V: Into<Vec<String>>,
+{
+    fn from(value: (T, U, V)) -> Self {
+        Self::new(value.0, value.1, value.2, Vec::new(), Vec::new())
+    }
+}
+
+impl<T, U, V, W> From<(T, U, V, W)> for Selector
+where
+    T: Into<Vec<Specifier>>,
+    U: Into<Vec<Tag>>,
+    V: Into<Vec<String>>,
+    W: Into<Vec<TagKey>>,
+{
+    fn from(value: (T, U, V, W)) -> Self {
+        Self::new(value.0, value.1, value.2, value.3, Vec::new())
+    }
+}
+
+impl<T, U, V, W, X> From<(T, U, V, W, X)> for Selector
+where
+    T: Into<Vec<Specifier>>,
+    U: Into<Vec<Tag>>,
+    V: Into<Vec<String>>,
+    W: Into<Vec<TagKey>>,
+    X: Into<Vec<IndexRange>>,
+{
+    fn from(value: (T, U, V, W, X)) -> Self {
+        Self::new(value.0, value.1, value.2, value.3, value.4)
+    }
+}
+
+// Index Range
+
+#[derive(new, Clone, Debug, Eq, PartialEq)]
+#[new(vis())]
+pub struct IndexRange(#[new(into)] String, Bound<IndexKey>, Bound<IndexKey>);
+
+impl IndexRange {
+    pub fn exact<N, K>(name: N, key: K) -> Self
+    where
+        N: Into<String>,
+        K: Into<IndexKey>,
+    {
+        let key = key.into();
+
+        Self::new(name, Bound::Included(key.clone()), Bound::Included(key))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    pub fn bounds(&self) -> (Bound<&IndexKey>, Bound<&IndexKey>) {
+        (self.1.as_ref(), self.2.as_ref())
+    }
+
+    pub fn contains(&self, key: &IndexKey) -> bool {
+        self.bounds().contains(key)
+    }
+}
+
+impl<T, R> From<(T, R)> for IndexRange
+where
+    T: Into<String>,
+    R: RangeBounds<IndexKey>,
+{
+    fn from(value: (T, R)) -> Self {
+        let lower = value.1.start_bound().cloned();
+        let upper = value.1.end_bound().cloned();
+
+        Self::new(value.0, lower, upper)
+    }
+}
+
+// Condition
+
+#[derive(new, Clone, Debug, Eq, PartialEq)]
+#[new(vis())]
+pub struct Condition(#[new(into)] Query, #[new(into)] Option<Position>);
+
+impl Condition {
+    pub fn query(&self) -> &Query {
+        &self.0
+    }
+
+    pub fn position(&self) -> Option<Position> {
+        self.1
+    }
+
+    pub fn take(self) -> (Query, Option<Position>) {
+        (self.0, self.1)
+    }
+}
+
+impl<T, U> From<(T, U)> for Condition
+where
+    T: Into<Query>,
+    U: Into<Option<Position>>,
+{
+    fn from(value: (T, U)) -> Self {
+        Self::new(value.0, value.1)
+    }
+}
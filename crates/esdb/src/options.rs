@@ -0,0 +1,525 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{
+        self,
+        Debug,
+        Formatter,
+    },
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+use derive_more::Display;
+use ed25519_dalek::VerifyingKey;
+use fjall::{
+    CompressionType,
+    Config,
+    PartitionCreateOptions,
+    PersistMode,
+};
+
+use crate::model::{
+    Event,
+    Identifier,
+    IndexKey,
+};
+
+// =================================================================================================
+// Options
+// =================================================================================================
+
+// Durability
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Durability {
+    #[default]
+    Buffered,
+    Periodic(Duration),
+    Sync,
+}
+
+impl Durability {
+    pub(crate) fn mode(self) -> Option<PersistMode> {
+        match self {
+            Self::Buffered | Self::Periodic(_) => None,
+            Self::Sync => Some(PersistMode::SyncAll),
+        }
+    }
+
+    fn interval(self) -> Option<u16> {
+        match self {
+            Self::Periodic(interval) => {
+                Some(u16::try_from(interval.as_millis().max(1)).unwrap_or(u16::MAX))
+            }
+            Self::Buffered | Self::Sync => None,
+        }
+    }
+}
+
+// Encoding
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Encoding {
+    #[default]
+    Hashed,
+    Dictionary,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Database Options
+
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct DatabaseOptions {
+    cache_size: Option<u64>,
+    compaction_workers: Option<usize>,
+    flush_workers: Option<usize>,
+    journal_size: Option<u64>,
+    max_open_files: Option<usize>,
+    write_buffer_size: Option<u64>,
+}
+
+impl DatabaseOptions {
+    pub fn cache_size(mut self, bytes: u64) -> Self {
+        self.cache_size = Some(bytes);
+        self
+    }
+
+    pub fn compaction_workers(mut self, workers: usize) -> Self {
+        self.compaction_workers = Some(workers);
+        self
+    }
+
+    pub fn flush_workers(mut self, workers: usize) -> Self {
+        self.flush_workers = Some(workers);
+        self
+    }
+
+    pub fn journal_size(mut self, bytes: u64) -> Self {
+        self.journal_size = Some(bytes);
+        self
+    }
+
+    pub fn max_open_files(mut self, files: usize) -> Self {
+        self.max_open_files = Some(files);
+        self
+    }
+
+    pub fn write_buffer_size(mut self, bytes: u64) -> Self {
+        self.write_buffer_size = Some(bytes);
+        self
+    }
+}
+
+impl DatabaseOptions {
+    pub(crate) fn config<P>(&self, path: P, durability: Durability) -> Config
+    where
+        P: AsRef<Path>,
+    {
+        let mut config = Config::new(path).fsync_ms(durability.interval());
+
+        if let Some(bytes) = self.cache_size {
+            config = config.cache_size(bytes);
+        }
+
+        if let Some(workers) = self.compaction_workers {
+            config = config.compaction_workers(workers);
+        }
+
+        if let Some(workers) = self.flush_workers {
+            config = config.flush_workers(workers);
+        }
+
+        if let Some(bytes) = self.journal_size {
+            config = config.max_journaling_size(bytes);
+        }
+
+        if let Some(files) = self.max_open_files {
+            config = config.max_open_files(files);
+        }
+
+        if let Some(bytes) = self.write_buffer_size {
+            config = config.max_write_buffer_size(bytes);
+        }
+
+        config
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Partition Options
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    Lz4,
+    None,
+}
+
+impl From<Compression> for CompressionType {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::Lz4 => Self::Lz4,
+            Compression::None => Self::None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct PartitionOptions {
+    create: PartitionCreateOptions,
+}
+
+impl PartitionOptions {
+    pub fn block_size(mut self, bytes: u32) -> Self {
+        self.create = self.create.block_size(bytes);
+        self
+    }
+
+    pub fn bloom_filter_bits(mut self, bits: Option<u8>) -> Self {
+        self.create = self.create.bloom_filter_bits(bits);
+        self
+    }
+
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.create = self.create.compression(compression.into());
+        self
+    }
+}
+
+impl PartitionOptions {
+    pub(crate) fn create(&self) -> PartitionCreateOptions {
+        self.create.clone()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Extractor
+
+type ExtractorFn = dyn Fn(&Event) -> Vec<IndexKey> + Send + Sync;
+
+#[derive(Clone)]
+pub struct Extractor(Arc<ExtractorFn>);
+
+impl Extractor {
+    pub(crate) fn extract(&self, event: &Event) -> Vec<IndexKey> {
+        (self.0)(event)
+    }
+}
+
+impl Debug for Extractor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Extractor")
+    }
+}
+
+impl<F> From<F> for Extractor
+where
+    F: Fn(&Event) -> Vec<IndexKey> + Send + Sync + 'static,
+{
+    fn from(extractor: F) -> Self {
+        Self(Arc::new(extractor))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Retention
+
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct Retention {
+    pub(crate) age: Option<Duration>,
+    pub(crate) identifiers: Vec<Identifier>,
+}
+
+impl Retention {
+    pub fn identifier<I>(mut self, identifier: I) -> Self
+    where
+        I: Into<Identifier>,
+    {
+        self.identifiers.push(identifier.into());
+        self
+    }
+
+    pub fn older_than(mut self, age: Duration) -> Self {
+        self.age = Some(age);
+        self
+    }
+}
+
+// Quota
+
+#[derive(Clone, Debug, Default)]
+#[must_use]
+pub struct Quota {
+    pub(crate) events_per_second: Option<u64>,
+    pub(crate) event_bytes: Option<u64>,
+    pub(crate) daily_bytes: Option<u64>,
+    pub(crate) storage_bytes: Option<u64>,
+}
+
+impl Quota {
+    pub fn events_per_second(mut self, events: u64) -> Self {
+        self.events_per_second = Some(events);
+        self
+    }
+
+    pub fn event_bytes(mut self, bytes: u64) -> Self {
+        self.event_bytes = Some(bytes);
+        self
+    }
+
+    pub fn daily_bytes(mut self, bytes: u64) -> Self {
+        self.daily_bytes = Some(bytes);
+        self
+    }
+
+    pub fn storage_bytes(mut self, bytes: u64) -> Self {
+        self.storage_bytes = Some(bytes);
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+pub enum QuotaLimit {
+    #[display("events per second")]
+    EventsPerSecond,
+    #[display("bytes per event")]
+    EventBytes,
+    #[display("bytes per day")]
+    DailyBytes,
+    #[display("storage bytes")]
+    StorageBytes,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Stream Options
+
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct StreamOptions {
+    pub(crate) blob_threshold: Option<u64>,
+    pub(crate) create: bool,
+    pub(crate) data: PartitionOptions,
+    pub(crate) database: DatabaseOptions,
+    pub(crate) durability: Durability,
+    pub(crate) encoding: Encoding,
+    pub(crate) extractors: BTreeMap<String, Extractor>,
+    pub(crate) index: PartitionOptions,
+    pub(crate) indexing: Indexing,
+    pub(crate) quotas: BTreeMap<String, Quota>,
+    pub(crate) read_only: bool,
+    pub(crate) reference: PartitionOptions,
+    pub(crate) seed: Option<u64>,
+    pub(crate) trusted_keys: BTreeMap<String, VerifyingKey>,
+}
+
+impl Default for StreamOptions {
+    fn default() -> Self {
+        Self {
+            blob_threshold: None,
+            create: true,
+            data: PartitionOptions::default(),
+            database: DatabaseOptions::default(),
+            durability: Durability::default(),
+            encoding: Encoding::default(),
+            extractors: BTreeMap::new(),
+            index: PartitionOptions::default(),
+            indexing: Indexing::default(),
+            quotas: BTreeMap::new(),
+            read_only: false,
+            reference: PartitionOptions::default(),
+            seed: None,
+            trusted_keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl StreamOptions {
+    /// Events appended before age retention is first enabled have no recorded
+    /// append time. They are indexed with the time the stream is opened with
+    /// this option, so their age is counted from that point.
+    pub fn age_retention(mut self, age_retention: bool) -> Self {
+        self.indexing.timestamps = age_retention;
+        self
+    }
+
+    pub fn blob_threshold(mut self, bytes: u64) -> Self {
+        self.blob_threshold = Some(bytes);
+        self
+    }
+
+    pub fn create_if_missing(mut self, create: bool) -> Self {
+        self.create = create;
+        self
+    }
+
+    pub fn data(mut self, data: PartitionOptions) -> Self {
+        self.data = data;
+        self
+    }
+
+    pub fn database(mut self, database: DatabaseOptions) -> Self {
+        self.database = database;
+        self
+    }
+
+    pub fn durability(mut self, durability: Durability) -> Self {
+        self.durability = durability;
+        self
+    }
+
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn extractor<N, E>(mut self, name: N, extractor: E) -> Self
+    where
+        N: Into<String>,
+        E: Into<Extractor>,
+    {
+        self.extractors.insert(name.into(), extractor.into());
+        self
+    }
+
+    pub fn index(mut self, index: PartitionOptions) -> Self {
+        self.index = index;
+        self
+    }
+
+    pub fn prefix_index(mut self, prefix_index: bool) -> Self {
+        self.indexing.prefixes = prefix_index;
+        self
+    }
+
+    pub fn quota<N>(mut self, namespace: N, quota: Quota) -> Self
+    where
+        N: Into<String>,
+    {
+        self.quotas.insert(namespace.into(), quota);
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn reference(mut self, reference: PartitionOptions) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn trusted_key<K>(mut self, key_id: K, key: VerifyingKey) -> Self
+    where
+        K: Into<String>,
+    {
+        self.trusted_keys.insert(key_id.into(), key);
+        self
+    }
+}
+
+impl StreamOptions {
+    pub(crate) fn creates(&self) -> bool {
+        self.create && !self.read_only
+    }
+}
+
+// Indexing
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Indexing {
+    pub(crate) prefixes: bool,
+    pub(crate) timestamps: bool,
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        error::Error,
+        fs,
+        process,
+        time::Duration,
+    };
+
+    use fjall::PersistMode;
+
+    use crate::{
+        model::{
+            Event,
+            Query,
+            Stream,
+        },
+        options::{
+            Durability,
+            StreamOptions,
+        },
+    };
+
+    #[test]
+    fn durability_modes_map_to_persistence() {
+        assert_eq!(Durability::Buffered.mode(), None);
+        assert_eq!(Durability::Buffered.interval(), None);
+        assert_eq!(Durability::Sync.mode(), Some(PersistMode::SyncAll));
+        assert_eq!(Durability::Sync.interval(), None);
+
+        let periodic = Durability::Periodic(Duration::from_millis(250));
+
+        assert_eq!(periodic.mode(), None);
+        assert_eq!(periodic.interval(), Some(250));
+        assert_eq!(Durability::Periodic(Duration::ZERO).interval(), Some(1));
+        assert_eq!(
+            Durability::Periodic(Duration::from_secs(3600)).interval(),
+            Some(u16::MAX)
+        );
+    }
+
+    #[test]
+    fn appends_survive_reopen_in_each_durability_mode() -> Result<(), Box<dyn Error>> {
+        let root = env::temp_dir().join(format!("esdb-durability-{}", process::id()));
+
+        fs::remove_dir_all(&root).ok();
+
+        let modes = [
+            Durability::Buffered,
+            Durability::Periodic(Duration::from_millis(10)),
+            Durability::Sync,
+        ];
+
+        for (i, durability) in modes.into_iter().enumerate() {
+            let path = root.join(i.to_string());
+            let options = StreamOptions::default().durability(durability);
+            let mut stream = Stream::open(&path, options.clone())?;
+
+            stream.append([Event::new(b"placed".to_vec(), ("order-placed", 0), [])])?;
+
+            drop(stream);
+
+            let stream = Stream::open(&path, options)?;
+            let events = stream.query(Query::all(), None).count();
+
+            assert_eq!(events, 1, "{durability:?}");
+        }
+
+        fs::remove_dir_all(root)?;
+
+        Ok(())
+    }
+}
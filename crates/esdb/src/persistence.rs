@@ -1,37 +1,104 @@
+pub mod amendment;
+pub mod audit;
+pub mod backend;
+pub mod blob;
+pub mod chain;
 pub mod data;
 pub mod index;
+pub mod metadata;
 pub mod reference;
 
 use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
     error::Error,
+    iter,
     ops::{
         Deref,
         Range,
     },
-    path::Path,
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::{
+        Duration,
+        SystemTime,
+        UNIX_EPOCH,
+    },
 };
 
-use derive_more::Debug;
-use fancy_constructor::new;
-use fjall::{
-    Batch,
-    Config,
-    Keyspace,
-    PartitionHandle,
+use bytes::{
+    Buf,
+    BufMut,
+};
+use derive_more::{
+    Debug,
+    Display,
+    Error,
 };
+use ed25519_dalek::{
+    Signature,
+    Signer as _,
+    SigningKey,
+    VerifyingKey,
+};
+use fancy_constructor::new;
 use rapidhash::v3::{
     self,
     RapidSecrets,
 };
 
-use crate::model::{
-    Descriptor,
-    Event,
-    Identifier,
-    Position,
-    Specifier,
-    Tag,
-    Version,
+use crate::{
+    model::{
+        AuditEntry,
+        Descriptor,
+        Event,
+        Identifier,
+        IndexKey,
+        IndexRange,
+        Position,
+        Provenance,
+        Query,
+        Redaction,
+        Selector,
+        SequencedEvent,
+        Specifier,
+        Stats,
+        Stream,
+        Tag,
+        TagKey,
+        Usage,
+        Verification,
+        Version,
+    },
+    options::{
+        Durability,
+        Encoding,
+        Extractor,
+        PartitionOptions,
+        Quota,
+        QuotaLimit,
+        Retention,
+        StreamOptions,
+    },
+    persistence::{
+        backend::{
+            Backend,
+            Batch,
+            FjallBackend,
+            KvPair,
+            MemoryBackend,
+            PartitionHandle,
+        },
+        blob::Payload,
+    },
 };
 
 // =================================================================================================
@@ -40,84 +107,1451 @@ use crate::model::{
 
 // Configuration
 
+static BACKUP_BATCH_LEN: usize = 1024;
 static POSITION_LEN: usize = size_of::<u64>();
-static SEED: RapidSecrets = RapidSecrets::seed(0x2811_2017);
+static SEED: u64 = 0x2811_2017;
+static STREAM_NAME_LEN: usize = 192;
+
+static PARTITION_NAMES: [&str; 8] = [
+    amendment::PARTITION_NAME,
+    audit::PARTITION_NAME,
+    blob::PARTITION_NAME,
+    chain::PARTITION_NAME,
+    data::PARTITION_NAME,
+    index::PARTITION_NAME,
+    metadata::PARTITION_NAME,
+    reference::PARTITION_NAME,
+];
+
+// -------------------------------------------------------------------------------------------------
+
+// Context
+
+#[derive(new, Debug)]
+#[new(vis(pub(crate)))]
+pub struct Read<'a> {
+    partitions: &'a Partitions,
+}
+
+#[derive(new, Debug)]
+#[new(vis(pub(crate)))]
+pub struct Write<'a> {
+    #[debug("Batch")]
+    batch: &'a mut Batch,
+    partitions: &'a Partitions,
+    #[new(default)]
+    counts: BTreeMap<(u8, u64), i64>,
+    #[new(default)]
+    chain: Option<[u8; 32]>,
+    #[new(default)]
+    amended: Option<(u64, [u8; 32])>,
+    #[new(default)]
+    usage: BTreeMap<String, metadata::Pending>,
+    #[new(default)]
+    assigned: reference::Assigned,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Database
+
+#[derive(new, Clone, Debug)]
+#[new(vis())]
+pub struct Database {
+    #[debug("Backend")]
+    backend: Arc<dyn Backend>,
+    options: StreamOptions,
+    #[debug(skip)]
+    #[new(default)]
+    open: Arc<Mutex<BTreeSet<String>>>,
+}
+
+impl Database {
+    pub fn open<P>(path: P, options: StreamOptions) -> Result<Self, Box<dyn Error>>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+
+        if !options.creates() && !exists(path)? {
+            return Err(MissingDatabase::new(path).into());
+        }
+
+        let backend = FjallBackend::open(path, &options)?;
+
+        Ok(Self::with_backend(backend, options))
+    }
+
+    pub fn memory(options: StreamOptions) -> Self {
+        Self::with_backend(MemoryBackend::default(), options)
+    }
+
+    pub fn with_backend<B>(backend: B, options: StreamOptions) -> Self
+    where
+        B: Backend + 'static,
+    {
+        Self::new(Arc::new(backend), options)
+    }
+
+    pub(crate) fn batch(&self) -> Batch {
+        Batch::new(self.backend.clone())
+    }
+
+    pub(crate) fn options(&self) -> &StreamOptions {
+        &self.options
+    }
+}
+
+impl AsRef<dyn Backend> for Database {
+    fn as_ref(&self) -> &(dyn Backend + 'static) {
+        self.backend.as_ref()
+    }
+}
+
+fn exists(path: &Path) -> Result<bool, Box<dyn Error>> {
+    Ok(path.exists() && path.read_dir()?.next().is_some())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Streams
+
+impl Database {
+    pub fn stream(&self, name: &str) -> Result<Stream, Box<dyn Error>> {
+        Stream::from_database(self.clone(), Some(name))
+    }
+
+    pub fn streams(&self) -> Vec<String> {
+        let suffix = partition_name(Some(""), data::PARTITION_NAME);
+
+        let mut streams = self
+            .backend
+            .partitions()
+            .iter()
+            .filter_map(|name| name.strip_suffix(&suffix).map(ToOwned::to_owned))
+            .collect::<Vec<_>>();
+
+        streams.sort();
+        streams
+    }
+
+    pub fn drop_stream(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        validate(name)?;
+
+        if self.options.read_only {
+            return Err(ReadOnly.into());
+        }
+
+        let open = self.open.lock().expect("stream lock poisoned");
+
+        if open.contains(name) {
+            return Err(StreamOpen::new(name).into());
+        }
+
+        for partition in PARTITION_NAMES {
+            let partition = partition_name(Some(name), partition);
+
+            if self.backend.partition_exists(&partition) {
+                self.backend.delete_partition(&partition)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Database {
+    pub(crate) fn lease(&self, name: &str) -> Result<Lease, Box<dyn Error>> {
+        let mut open = self.open.lock().expect("stream lock poisoned");
+
+        if !open.insert(name.to_owned()) {
+            return Err(StreamOpen::new(name).into());
+        }
+
+        Ok(Lease::new(self.open.clone(), name))
+    }
+}
+
+fn validate(name: &str) -> Result<(), Box<dyn Error>> {
+    let valid = !name.is_empty()
+        && name.len() <= STREAM_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid stream name \"{name}\"").into())
+    }
+}
+
+// Lease
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub(crate) struct Lease {
+    #[debug(skip)]
+    open: Arc<Mutex<BTreeSet<String>>>,
+    #[new(into)]
+    name: String,
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.open
+            .lock()
+            .expect("stream lock poisoned")
+            .remove(&self.name);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Partitions
+
+#[derive(new, Clone, Debug)]
+#[new(vis())]
+pub struct Partitions {
+    amendment: Option<PartitionHandle>,
+    audit: Option<PartitionHandle>,
+    blob: Option<PartitionHandle>,
+    chain: Option<PartitionHandle>,
+    data: PartitionHandle,
+    index: PartitionHandle,
+    metadata: Option<PartitionHandle>,
+    reference: PartitionHandle,
+    #[new(default)]
+    blob_threshold: Option<u64>,
+    #[new(default)]
+    checksums: bool,
+    #[new(default)]
+    encoding: Encoding,
+    #[new(default)]
+    indexes: BTreeSet<u64>,
+    #[new(default)]
+    indexed: Indexed,
+    #[new(default)]
+    legacy: bool,
+    #[new(default)]
+    namespaces: Vec<String>,
+    #[new(default)]
+    timestamps: bool,
+    #[new(default)]
+    trusted_keys: BTreeMap<String, VerifyingKey>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Indexed {
+    keys: bool,
+    names: bool,
+    prefixes: bool,
+}
+
+pub fn partitions(database: &Database, stream: Option<&str>) -> Result<Partitions, Box<dyn Error>> {
+    if let Some(stream) = stream {
+        validate(stream)?;
+    }
+
+    let mut partitions = Partitions::new(
+        amendment::partition(database, stream)?,
+        audit::partition(database, stream)?,
+        blob::partition(database, stream)?,
+        chain::partition(database, stream)?,
+        data::partition(database, stream)?,
+        index::partition(database, stream)?,
+        metadata::partition(database, stream)?,
+        reference::partition(database, stream)?,
+    );
+
+    partitions.blob_threshold = database.options.blob_threshold;
+    partitions.checksums = metadata::is_checksummed(&Read::new(&partitions))?;
+    partitions.indexes = metadata::extracted(&Read::new(&partitions))?;
+    partitions.indexed.keys = metadata::is_key_indexed(&Read::new(&partitions))?;
+    partitions.indexed.names = metadata::is_names_indexed(&Read::new(&partitions))?;
+    partitions.indexed.prefixes = metadata::is_prefix_indexed(&Read::new(&partitions))?;
+    partitions.namespaces = database.options.quotas.keys().cloned().collect();
+    partitions.timestamps = metadata::is_timestamped(&Read::new(&partitions))?;
+    partitions.trusted_keys = database.options.trusted_keys.clone();
+
+    Ok(partitions)
+}
+
+fn partition(
+    database: &Database,
+    stream: Option<&str>,
+    name: &str,
+    partition: &PartitionOptions,
+) -> Result<PartitionHandle, Box<dyn Error>> {
+    let name = partition_name(stream, name);
+
+    if !database.options.creates() && !database.backend.partition_exists(&name) {
+        return Err(MissingPartition::new(name).into());
+    }
+
+    let partition = database.backend.open_partition(&name, partition)?;
+
+    Ok(PartitionHandle::new(partition))
+}
+
+fn optional_partition(
+    database: &Database,
+    stream: Option<&str>,
+    name: &str,
+    partition: &PartitionOptions,
+    create: bool,
+) -> Result<Option<PartitionHandle>, Box<dyn Error>> {
+    let name = partition_name(stream, name);
+    let data = partition_name(stream, data::PARTITION_NAME);
+
+    let creates = match (create, database.backend.partition_exists(&data)) {
+        (false, _) => false,
+        (true, true) => !database.options.read_only,
+        (true, false) => database.options.creates(),
+    };
+
+    if !creates && !database.backend.partition_exists(&name) {
+        return Ok(None);
+    }
+
+    let partition = database.backend.open_partition(&name, partition)?;
+
+    Ok(Some(PartitionHandle::new(partition)))
+}
+
+fn partition_name(stream: Option<&str>, name: &str) -> String {
+    match stream {
+        Some(stream) => format!("{stream}#{name}"),
+        None => name.to_owned(),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Encoding
+
+static VARINT_BITS: u32 = 7;
+static VARINT_CONTINUATION: u8 = 0b1000_0000;
+static VARINT_MASK: u8 = 0b0111_1111;
+
+impl Encoding {
+    fn put_id<B>(self, buf: &mut B, id: u64)
+    where
+        B: BufMut,
+    {
+        match self {
+            Self::Hashed => buf.put_u64(id),
+            Self::Dictionary => {
+                let mut id = id;
+
+                while id > u64::from(VARINT_MASK) {
+                    buf.put_u8(id.to_le_bytes()[0] & VARINT_MASK | VARINT_CONTINUATION);
+
+                    id >>= VARINT_BITS;
+                }
+
+                buf.put_u8(id.to_le_bytes()[0]);
+            }
+        }
+    }
+
+    fn get_id<B>(self, buf: &mut B) -> u64
+    where
+        B: Buf,
+    {
+        match self {
+            Self::Hashed => buf.get_u64(),
+            Self::Dictionary => {
+                let mut id = 0;
+                let mut shift = 0;
+
+                loop {
+                    let byte = buf.get_u8();
+
+                    id |= u64::from(byte & VARINT_MASK) << shift;
+
+                    if byte & VARINT_CONTINUATION == 0 {
+                        return id;
+                    }
+
+                    shift += VARINT_BITS;
+                }
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Errors
+
+#[derive(new, Debug, Display, Error)]
+#[display("database {} does not exist", path.display())]
+#[new(vis())]
+pub struct MissingDatabase {
+    #[new(into)]
+    path: PathBuf,
+}
+
+impl MissingDatabase {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[derive(new, Debug, Display, Error)]
+#[display("partition {name} does not exist")]
+#[new(vis())]
+pub struct MissingPartition {
+    name: String,
+}
+
+impl MissingPartition {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(Debug, Display, Error)]
+#[display("database is opened read-only")]
+pub struct ReadOnly;
+
+#[derive(new, Debug, Display, Error)]
+#[display("stream {name} is already open")]
+#[new(vis())]
+pub struct StreamOpen {
+    #[new(into)]
+    name: String,
+}
+
+impl StreamOpen {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[derive(new, Debug, Display, Error)]
+#[display("event at position {} failed checksum verification", position.value())]
+#[new(vis(pub(crate)))]
+pub struct Corrupted {
+    position: Position,
+}
+
+impl Corrupted {
+    pub fn position(&self) -> Position {
+        self.position
+    }
+}
+
+#[derive(new, Clone, Debug, Display, Error)]
+#[display("{limit} quota exceeded for namespace {namespace}")]
+#[new(vis(pub(crate)))]
+pub struct QuotaExceeded {
+    namespace: String,
+    limit: QuotaLimit,
+}
+
+impl QuotaExceeded {
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn limit(&self) -> QuotaLimit {
+        self.limit
+    }
+}
+
+#[derive(new, Debug, Display, Error)]
+#[display("event chain verification failed at position {}", position.value())]
+#[new(vis(pub(crate)))]
+pub struct Tampered {
+    position: Position,
+}
+
+impl Tampered {
+    pub fn position(&self) -> Position {
+        self.position
+    }
+}
+
+#[derive(new, Clone, Debug, Display, Error)]
+#[display("{} signature for key {key_id}", if *trusted { "invalid" } else { "untrusted" })]
+#[new(vis(pub(crate)))]
+pub struct Unverified {
+    key_id: String,
+    trusted: bool,
+}
+
+impl Unverified {
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn is_trusted(&self) -> bool {
+        self.trusted
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Hasher
+
+#[derive(new, Clone, Copy, Debug)]
+#[new(vis())]
+pub struct Hasher(RapidSecrets);
+
+impl Hasher {
+    fn hash(&self, value: &str) -> u64 {
+        v3::rapidhash_v3_seeded(value.as_bytes(), &self.0)
+    }
+}
+
+impl From<u64> for Hasher {
+    fn from(seed: u64) -> Self {
+        Self::new(RapidSecrets::seed(seed))
+    }
+}
+
+pub fn hasher(database: &Database, partitions: &Partitions) -> Result<Hasher, Box<dyn Error>> {
+    let read = Read::new(partitions);
+
+    match (metadata::seed(&read)?, database.options.seed) {
+        (Some(stored), Some(seed)) if stored != seed => {
+            Err(format!("hash seed {seed:#x} does not match stored seed {stored:#x}").into())
+        }
+        (Some(stored), _) => Ok(stored.into()),
+        (None, seed) => {
+            let seed = seed.unwrap_or(SEED);
+
+            if seed != SEED && !data::is_empty(&read)? {
+                return Err("hash seed cannot be changed for existing events".into());
+            }
+
+            if !database.options.read_only {
+                let mut batch = database.batch();
+                let mut write = Write::new(&mut batch, partitions);
+
+                metadata::insert_seed(&mut write, seed)?;
+
+                batch.durability(Durability::Sync).commit()?;
+            }
+
+            Ok(seed.into())
+        }
+    }
+}
+
+pub fn encoding(database: &Database, partitions: &mut Partitions) -> Result<(), Box<dyn Error>> {
+    let read = Read::new(partitions);
+
+    if metadata::is_dictionary(&read)? {
+        partitions.encoding = Encoding::Dictionary;
+
+        return Ok(());
+    }
+
+    if database.options.encoding == Encoding::Hashed {
+        return Ok(());
+    }
+
+    if !data::is_empty(&read)? {
+        return Err("dictionary encoding cannot be enabled for existing events".into());
+    }
+
+    if !database.options.read_only {
+        let mut batch = database.batch();
+        let mut write = Write::new(&mut batch, partitions);
+
+        metadata::insert_dictionary(&mut write)?;
+
+        batch.durability(Durability::Sync).commit()?;
+    }
+
+    partitions.encoding = Encoding::Dictionary;
+
+    Ok(())
+}
+
+pub fn format(database: &Database, partitions: &mut Partitions) -> Result<(), Box<dyn Error>> {
+    let read = Read::new(partitions);
+
+    if metadata::format(&read)?.is_some() {
+        return Ok(());
+    }
+
+    let legacy = !partitions.checksums && !data::is_empty(&read)?;
+
+    if database.options.read_only {
+        partitions.legacy = legacy;
+
+        return Ok(());
+    }
+
+    if legacy {
+        data::migrate(database, partitions)?;
+    }
+
+    let mut batch = database.batch();
+    let mut write = Write::new(&mut batch, partitions);
+
+    metadata::insert_format(&mut write, data::FORMAT_VERSION)?;
+    metadata::remove_migrated(&mut write)?;
+
+    batch.durability(Durability::Sync).commit()?;
+
+    Ok(())
+}
+
+pub fn checksum(database: &Database, partitions: &mut Partitions) -> Result<(), Box<dyn Error>> {
+    if database.options.read_only || partitions.checksums {
+        return Ok(());
+    }
+
+    data::backfill_checksums(database, partitions)?;
+
+    let mut batch = database.batch();
+    let mut write = Write::new(&mut batch, partitions);
+
+    metadata::insert_checksummed(&mut write)?;
+
+    batch.durability(Durability::Sync).commit()?;
+
+    partitions.checksums = true;
+
+    Ok(())
+}
+
+pub fn rechain(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+    if database.options.read_only {
+        return Ok(());
+    }
+
+    chain::backfill(database, partitions)
+}
+
+pub fn recount(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+    if database.options.read_only {
+        return Ok(());
+    }
+
+    metadata::recount(database, partitions)
+}
+
+pub fn measure(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+    if database.options.read_only {
+        return Ok(());
+    }
+
+    metadata::measure(database, partitions)
+}
+
+pub fn reindex(
+    database: &Database,
+    partitions: &mut Partitions,
+    hasher: Hasher,
+) -> Result<(), Box<dyn Error>> {
+    if database.options.read_only {
+        return Ok(());
+    }
+
+    let keys = !partitions.indexed.keys;
+    let names = !partitions.indexed.names;
+    let prefixes = database.options.indexing.prefixes && !partitions.indexed.prefixes;
+    let timestamps = database.options.indexing.timestamps != partitions.timestamps;
+
+    let extractors = database
+        .options
+        .extractors
+        .iter()
+        .map(|(name, extractor)| (hasher.hash(name), extractor))
+        .collect::<BTreeMap<_, _>>();
+    let added = extractors
+        .iter()
+        .filter(|(name, _)| !partitions.indexes.contains(name))
+        .collect::<Vec<_>>();
+    let removed = partitions
+        .indexes
+        .iter()
+        .filter(|name| !extractors.contains_key(name))
+        .copied()
+        .collect::<Vec<_>>();
+
+    if !keys && !names && !prefixes && !timestamps && added.is_empty() && removed.is_empty() {
+        return Ok(());
+    }
+
+    if keys {
+        index::backfill_keys(database, partitions, hasher)?;
+    }
+
+    if names {
+        reference::backfill_names(database, partitions)?;
+    }
+
+    if prefixes {
+        index::backfill_prefixes(database, partitions)?;
+    }
+
+    if timestamps && database.options.indexing.timestamps {
+        index::backfill_timestamps(database, partitions)?;
+    }
+
+    for (name, extractor) in &added {
+        index::backfill_extracted(database, partitions, **name, extractor)?;
+    }
+
+    for name in &removed {
+        index::clear_extracted(database, partitions, *name)?;
+    }
+
+    let mut batch = database.batch();
+    let mut write = Write::new(&mut batch, partitions);
+
+    if keys {
+        metadata::insert_key_indexed(&mut write)?;
+    }
+
+    if names {
+        metadata::insert_names_indexed(&mut write)?;
+    }
+
+    if prefixes {
+        metadata::insert_prefix_indexed(&mut write)?;
+    }
+
+    if timestamps && database.options.indexing.timestamps {
+        metadata::insert_timestamped(&mut write)?;
+    } else if timestamps {
+        metadata::remove_timestamped(&mut write)?;
+    }
+
+    for (name, _) in &added {
+        metadata::insert_extracted(&mut write, **name)?;
+    }
+
+    for name in &removed {
+        metadata::remove_extracted(&mut write, *name)?;
+    }
+
+    batch.durability(Durability::Sync).commit()?;
+
+    partitions.indexes = extractors.into_keys().collect();
+    partitions.indexed.keys = true;
+    partitions.indexed.names = true;
+    partitions.indexed.prefixes = partitions.indexed.prefixes || prefixes;
+    partitions.timestamps = database.options.indexing.timestamps;
+
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Backup
+
+impl Database {
+    pub fn backup<P>(&self, path: P) -> Result<(), Box<dyn Error>>
+    where
+        P: AsRef<Path>,
+    {
+        copy(self, &create(path)?, None)
+    }
+}
+
+pub fn restore<P, Q>(backup: P, path: Q, position: Option<Position>) -> Result<(), Box<dyn Error>>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    copy(
+        &Database::open(backup, StreamOptions::default().read_only(true))?,
+        &create(path)?,
+        position,
+    )
+}
+
+fn create<P>(path: P) -> Result<Database, Box<dyn Error>>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+
+    if exists(path)? {
+        return Err(format!("destination {} is not empty", path.display()).into());
+    }
+
+    Database::open(path, StreamOptions::default())
+}
+
+fn copy(
+    source: &Database,
+    target: &Database,
+    position: Option<Position>,
+) -> Result<(), Box<dyn Error>> {
+    let source = Database::new(
+        source.backend.snapshot(),
+        source.options.clone().read_only(true),
+    );
+
+    let named = source.streams().into_iter().map(Some);
+    let streams = iter::once(None)
+        .filter(|_| source.backend.partition_exists(data::PARTITION_NAME))
+        .chain(named);
+
+    for stream in streams {
+        let stream = stream.as_deref();
+
+        let mut source_partitions = partitions(&source, stream)?;
+
+        encoding(&source, &mut source_partitions)?;
+        format(&source, &mut source_partitions)?;
+
+        let target_partitions = partitions(target, stream)?;
+
+        if let (Some(source_amendment), Some(target_amendment)) =
+            (&source_partitions.amendment, &target_partitions.amendment)
+        {
+            transfer(
+                target,
+                target_amendment,
+                amendment::backup(source_amendment, position),
+            )?;
+        }
+
+        if let (Some(source_audit), Some(target_audit)) =
+            (&source_partitions.audit, &target_partitions.audit)
+        {
+            transfer(target, target_audit, audit::backup(source_audit, position))?;
+        }
+
+        let target_blob = optional_partition(
+            target,
+            stream,
+            blob::PARTITION_NAME,
+            &target.options.data,
+            source_partitions.blob.is_some(),
+        )?;
+
+        if let (Some(source_blob), Some(target_blob)) = (&source_partitions.blob, &target_blob) {
+            transfer(target, target_blob, blob::backup(source_blob, position))?;
+        }
+
+        if let (Some(source_chain), Some(target_chain)) =
+            (&source_partitions.chain, &target_partitions.chain)
+        {
+            transfer(target, target_chain, chain::backup(source_chain, position))?;
+        }
+
+        transfer(
+            target,
+            &target_partitions.data,
+            data::backup(&source_partitions.data, position),
+        )?;
+        transfer(
+            target,
+            &target_partitions.index,
+            index::backup(&source_partitions.index, position),
+        )?;
+
+        if let (Some(source_metadata), Some(target_metadata)) =
+            (&source_partitions.metadata, &target_partitions.metadata)
+        {
+            transfer(
+                target,
+                target_metadata,
+                metadata::backup(source_metadata, position),
+            )?;
+        }
+
+        transfer(
+            target,
+            &target_partitions.reference,
+            reference::backup(&Read::new(&source_partitions), position)?,
+        )?;
+    }
+
+    target.backend.persist()?;
+
+    Ok(())
+}
+
+fn transfer<I>(
+    target: &Database,
+    partition: &PartitionHandle,
+    entries: I,
+) -> Result<(), Box<dyn Error>>
+where
+    I: Iterator<Item = Result<KvPair, Box<dyn Error>>>,
+{
+    let mut batch = target.batch();
+
+    for entry in entries {
+        let (key, value) = entry?;
+
+        batch.insert(partition, key, value);
+
+        if batch.len() >= BACKUP_BATCH_LEN {
+            batch.commit()?;
+            batch = target.batch();
+        }
+    }
+
+    batch.commit()?;
+
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Insertion
+
+pub fn validate_event(event: &Event) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if event.tags.len() > data::MAX_TAGS_LEN {
+        return Err(format!("events are limited to {} tags", data::MAX_TAGS_LEN).into());
+    }
+
+    if let Some(tag) = event.tags.iter().find(|tag| !tag.is_encodable()) {
+        return Err(format!("tag \"{}\" contains a reserved character", tag.value()).into());
+    }
+
+    Ok(())
+}
+
+pub fn assign(write: &mut Write<'_>, event: &mut HashedEvent) -> Result<(), Box<dyn Error>> {
+    match write.partitions.encoding {
+        Encoding::Hashed => Ok(()),
+        Encoding::Dictionary => reference::assign(write, event),
+    }
+}
+
+pub fn insert(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+) -> Result<(), Box<dyn Error>> {
+    chain::insert(write, position, event)?;
+    data::insert(write, position, event)?;
+    index::insert(write, position, event);
+    metadata::insert(write, event);
+    reference::insert(write, event);
+
+    Ok(())
+}
+
+pub fn limit(
+    write: &Write<'_>,
+    events: &[HashedEvent],
+    quotas: &BTreeMap<String, Quota>,
+    rates: &BTreeMap<String, (u64, u64)>,
+) -> Result<Option<QuotaExceeded>, Box<dyn Error>> {
+    let read = Read::new(write.partitions);
+    let timestamp = timestamp();
+    let second = timestamp / 1000;
+
+    for (namespace, quota) in quotas {
+        let sizes = events
+            .iter()
+            .filter(|event| {
+                event
+                    .tags
+                    .iter()
+                    .any(|tag| metadata::contains(namespace, tag))
+            })
+            .map(|event| event.data.len() as u64)
+            .collect::<Vec<_>>();
+
+        if sizes.is_empty() {
+            continue;
+        }
+
+        let events = sizes.len() as u64;
+        let bytes = sizes.iter().sum::<u64>();
+        let pending = metadata::pending_usage(write, namespace);
+        let exceeded = |limit| Ok(Some(QuotaExceeded::new(namespace.clone(), limit)));
+
+        if let Some(limit) = quota.event_bytes
+            && sizes.iter().any(|size| *size > limit)
+        {
+            return exceeded(QuotaLimit::EventBytes);
+        }
+
+        if let Some(limit) = quota.events_per_second {
+            let rate = rates
+                .get(namespace)
+                .filter(|(window, _)| *window == second)
+                .map_or(0, |(_, events)| *events);
+
+            if rate + pending.events + events > limit {
+                return exceeded(QuotaLimit::EventsPerSecond);
+            }
+        }
+
+        if let Some(limit) = quota.daily_bytes {
+            let daily = metadata::daily_usage(&read, namespace, metadata::day(timestamp))?;
+
+            if daily + pending.daily + bytes > limit {
+                return exceeded(QuotaLimit::DailyBytes);
+            }
+        }
+
+        if let Some(limit) = quota.storage_bytes {
+            let storage =
+                metadata::storage_usage(&read, namespace)?.saturating_add_signed(pending.storage);
+
+            if storage + bytes > limit {
+                return exceeded(QuotaLimit::StorageBytes);
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn meter(
+    rates: &mut BTreeMap<String, (u64, u64)>,
+    quotas: &BTreeMap<String, Quota>,
+    events: &[(Position, HashedEvent)],
+) {
+    let second = timestamp() / 1000;
+
+    for namespace in quotas.keys() {
+        let events = events
+            .iter()
+            .filter(|(_, event)| {
+                event
+                    .tags
+                    .iter()
+                    .any(|tag| metadata::contains(namespace, tag))
+            })
+            .count() as u64;
+
+        let rate = rates.entry(namespace.clone()).or_default();
+
+        if rate.0 != second {
+            *rate = (second, 0);
+        }
+
+        rate.1 += events;
+    }
+}
+
+pub fn flush(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let unused = metadata::flush(write)?;
+
+    reference::remove_tags(write, &unused)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Removal
+
+pub fn remove(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+    archived: u64,
+) -> Result<(), Box<dyn Error>> {
+    chain::tombstone(write, position, event, archived)?;
+    data::remove(write, position, event, archived)?;
+    index::remove(write, position, event)?;
+    metadata::remove(write, event);
+
+    Ok(())
+}
+
+pub fn expired(
+    read: &Read<'_>,
+    hasher: Hasher,
+    retention: &Retention,
+) -> Result<Vec<u64>, Box<dyn Error>> {
+    let identified = if retention.identifiers.is_empty() {
+        None
+    } else {
+        let specifiers = retention
+            .identifiers
+            .iter()
+            .map(|identifier| Specifier::from((identifier.clone(), None)))
+            .collect::<Vec<_>>();
+        let mut query = hasher.query(Query::from(vec![Selector::from((specifiers, Vec::new()))]));
+
+        resolve_query(read, None, &mut query)?;
+
+        Some(index::iterate(read, None, &query).collect::<BTreeSet<_>>())
+    };
+
+    if retention.age.is_some() && !read.partitions.timestamps {
+        return Err("age-based retention is not enabled for this stream".into());
+    }
+
+    let aged = retention.age.map(|age| {
+        let age = u64::try_from(age.as_millis()).unwrap_or(u64::MAX);
+
+        index::aged(read, timestamp().saturating_sub(age)).collect::<BTreeSet<_>>()
+    });
+
+    match (identified, aged) {
+        (Some(identified), Some(aged)) => Ok(identified.intersection(&aged).copied().collect()),
+        (Some(positions), None) | (None, Some(positions)) => Ok(positions.into_iter().collect()),
+        (None, None) => Err("retention policy requires an identifier or an age".into()),
+    }
+}
+
+pub fn timestamp() -> u64 {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Redaction
+
+pub fn redact(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+    replacement: &HashedEvent,
+    redaction: &Redaction,
+) -> Result<(), Box<dyn Error>> {
+    chain::redact(write, position, replacement)?;
+    data::redact(write, position, replacement)?;
+    index::redact(write, position, event, replacement);
+    metadata::redact(write, event, replacement);
+    audit::insert(write, position, redaction, timestamp())
+}
+
+pub fn audit(read: &Read<'_>) -> Result<Vec<AuditEntry>, Box<dyn Error>> {
+    audit::all(read)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Verification
+
+pub fn chain_hash(read: &Read<'_>, position: Position) -> Result<[u8; 32], Box<dyn Error>> {
+    chain::hash(read, position)
+}
+
+pub fn amendment_head(read: &Read<'_>) -> Result<(u64, [u8; 32]), Box<dyn Error>> {
+    amendment::head(read)
+}
+
+pub fn amendment_hash(read: &Read<'_>, len: u64) -> Result<[u8; 32], Box<dyn Error>> {
+    amendment::hash(read, len)
+}
+
+pub fn verify_chain(
+    read: &Read<'_>,
+    start: Position,
+    end: Position,
+) -> Result<Verification, Box<dyn Error>> {
+    chain::verify(read, start, end)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Retrieval
+
+pub fn get(read: &Read<'_>, position: Position) -> Result<Option<SequencedEvent>, Box<dyn Error>> {
+    let Some(record) = data::get(read, position)? else {
+        return Ok(None);
+    };
+
+    let identifier = reference::identifier(read, record.identifier)?;
+    let descriptor = Descriptor::from((identifier, record.version));
+    let tags = record
+        .tags
+        .iter()
+        .map(|tag| reference::tag(read, *tag))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut event = Event::new(record.data, descriptor, tags);
+
+    event.signature = record.signature;
+
+    let provenance = provenance(&read.partitions.trusted_keys, &event, record.redacted);
+    let archived = record
+        .archived
+        .map(|archived| UNIX_EPOCH + Duration::from_millis(archived));
+
+    Ok(Some(SequencedEvent::new(
+        event,
+        position,
+        archived,
+        record.redacted,
+        provenance,
+    )))
+}
+
+pub fn payload(read: &Read<'_>, position: Position) -> Result<Option<Payload>, Box<dyn Error>> {
+    data::payload(read, position)
+}
+
+pub fn resolve(read: &Read<'_>, event: &mut HashedEvent) -> Result<(), Box<dyn Error>> {
+    match read.partitions.encoding {
+        Encoding::Hashed => Ok(()),
+        Encoding::Dictionary => reference::resolve(read, event),
+    }
+}
+
+// Query
+
+pub fn query<'a>(
+    read: Read<'a>,
+    position: Option<Position>,
+    mut query: HashedQuery,
+) -> Box<dyn Iterator<Item = Result<SequencedEvent, Box<dyn Error>>> + 'a> {
+    if let Err(err) = resolve_query(&read, None, &mut query) {
+        return Box::new(iter::once(Err(err)));
+    }
+
+    let positions = match positions(&read, position, &query) {
+        Ok(positions) => positions,
+        Err(err) => return Box::new(iter::once(Err(err))),
+    };
+
+    Box::new(positions.map(move |position| {
+        let position = Position::from(position);
+        let event = get(&read, position)?.ok_or("indexed event missing from data")?;
+
+        Ok(event)
+    }))
+}
+
+pub fn resolve_query(
+    read: &Read<'_>,
+    write: Option<&Write<'_>>,
+    query: &mut HashedQuery,
+) -> Result<(), Box<dyn Error>> {
+    match read.partitions.encoding {
+        Encoding::Hashed => Ok(()),
+        Encoding::Dictionary => {
+            let assigned = write.map(|write| &write.assigned);
+
+            reference::resolve_query(read, assigned, query)
+        }
+    }
+}
+
+pub fn positions(
+    read: &Read<'_>,
+    position: Option<Position>,
+    query: &HashedQuery,
+) -> Result<Box<dyn Iterator<Item = u64>>, Box<dyn Error>> {
+    if query.is_keyed() && !read.partitions.indexed.keys {
+        return Err("tag key queries require the tag key index".into());
+    }
+
+    if query.is_prefixed() && !read.partitions.indexed.prefixes {
+        return Err("tag prefix queries require the tag prefix index".into());
+    }
+
+    if let Some(range) = query
+        .index_ranges()
+        .find(|range| !read.partitions.indexes.contains(&range.hash()))
+    {
+        return Err(format!("index `{}` is not registered", range.name()).into());
+    }
+
+    if query.is_unrestricted() {
+        Ok(Box::new(data::iterate(read, position)))
+    } else {
+        Ok(Box::new(index::iterate(read, position, query)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Statistics
+
+pub fn stats(
+    read: &Read<'_>,
+    rates: &BTreeMap<String, (u64, u64)>,
+) -> Result<Stats, Box<dyn Error>> {
+    let partitions = [
+        read.partitions.blob.as_ref(),
+        Some(&read.partitions.data),
+        Some(&read.partitions.index),
+        read.partitions.metadata.as_ref(),
+        Some(&read.partitions.reference),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|partition| (partition.name().to_owned(), partition.disk_space()))
+    .collect();
+
+    let identifiers = metadata::identifier_counts(read)?
+        .into_iter()
+        .map(|(hash, count)| Ok((reference::identifier(read, hash)?, count)))
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    let tags = metadata::tag_counts(read)?
+        .into_iter()
+        .map(|(hash, count)| Ok((reference::tag(read, hash)?, count)))
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    let timestamp = timestamp();
+    let second = timestamp / 1000;
+
+    let namespaces = if metadata::is_available(read) {
+        read.partitions.namespaces.as_slice()
+    } else {
+        &[]
+    };
+
+    let usage = namespaces
+        .iter()
+        .map(|namespace| {
+            let storage = metadata::storage_usage(read, namespace)?;
+            let daily = metadata::daily_usage(read, namespace, metadata::day(timestamp))?;
+            let rate = rates
+                .get(namespace)
+                .filter(|(window, _)| *window == second)
+                .map_or(0, |(_, events)| *events);
+
+            Ok((namespace.clone(), Usage::new(storage, daily, rate)))
+        })
+        .collect::<Result<_, Box<dyn Error>>>()?;
+
+    Ok(Stats::new(
+        data::len(read)?,
+        reference::identifiers_len(read)?,
+        reference::tags_len(read)?,
+        identifiers,
+        tags,
+        partitions,
+        usage,
+    ))
+}
 
 // -------------------------------------------------------------------------------------------------
 
-// Context
+// Record
 
 #[derive(new, Debug)]
-#[new(vis(pub(crate)))]
-pub struct Read<'a> {
-    partitions: &'a Partitions,
+#[new(vis())]
+pub struct Record {
+    data: Vec<u8>,
+    identifier: u64,
+    version: Version,
+    tags: Vec<u64>,
+    archived: Option<u64>,
+    redacted: bool,
+    signature: Option<(String, Signature)>,
+    offloaded: Option<u64>,
 }
 
+// -------------------------------------------------------------------------------------------------
+
+// Amendment
+
 #[derive(new, Debug)]
-#[new(vis(pub(crate)))]
-pub struct Write<'a> {
-    #[debug("Batch")]
-    batch: &'a mut Batch,
-    partitions: &'a Partitions,
+#[new(vis())]
+pub struct Amendment {
+    position: Position,
+    previous: [u8; 32],
+    current: [u8; 32],
 }
 
 // -------------------------------------------------------------------------------------------------
 
-// Database
+// Signatures
 
-#[derive(new, Debug)]
-#[new(vis())]
-pub struct Database {
-    #[debug("Keyspace")]
-    keyspace: Keyspace,
+static SIGNATURE_CONTEXT: &[u8] = b"esdb-event";
+
+pub fn sign(event: &mut Event, key_id: String, key: &SigningKey) -> Result<(), Box<dyn Error>> {
+    validate_key_id(&key_id)?;
+
+    let message = message(
+        &key_id,
+        event.descriptor.identifier().value(),
+        event.descriptor.version().value(),
+        event.tags.iter().map(Tag::encode),
+        &event.data,
+    );
+
+    event.signature = Some((key_id, key.sign(&message)));
+
+    Ok(())
 }
 
-impl AsRef<Keyspace> for Database {
-    fn as_ref(&self) -> &Keyspace {
-        &self.keyspace
+pub fn authenticate(read: &Read<'_>, event: &Event) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if let Some((key_id, _)) = &event.signature {
+        validate_key_id(key_id)?;
     }
-}
 
-pub fn database<P>(path: P) -> Result<Database, Box<dyn Error>>
-where
-    P: AsRef<Path>,
-{
-    Ok(Config::new(path).open().map(Database::new)?)
+    match provenance(&read.partitions.trusted_keys, event, false) {
+        Provenance::Untrusted(key_id) => Err(Unverified::new(key_id, false).into()),
+        Provenance::Invalid(key_id) => Err(Unverified::new(key_id, true).into()),
+        Provenance::Unsigned | Provenance::Verified(_) | Provenance::Redacted(_) => Ok(()),
+    }
 }
 
-// -------------------------------------------------------------------------------------------------
+pub fn provenance(
+    trusted_keys: &BTreeMap<String, VerifyingKey>,
+    event: &Event,
+    redacted: bool,
+) -> Provenance {
+    let Some((key_id, signature)) = &event.signature else {
+        return Provenance::Unsigned;
+    };
 
-// Partitions
+    if redacted {
+        return Provenance::Redacted(key_id.clone());
+    }
 
-#[derive(new, Debug)]
-#[new(vis())]
-pub struct Partitions {
-    #[debug("PartitionHandle(\"{}\")", data.name)]
-    data: PartitionHandle,
-    #[debug("PartitionHandle(\"{}\")", index.name)]
-    index: PartitionHandle,
-    #[debug("PartitionHandle(\"{}\")", reference.name)]
-    reference: PartitionHandle,
+    let Some(key) = trusted_keys.get(key_id) else {
+        return Provenance::Untrusted(key_id.clone());
+    };
+
+    let message = message(
+        key_id,
+        event.descriptor.identifier().value(),
+        event.descriptor.version().value(),
+        event.tags.iter().map(Tag::encode),
+        &event.data,
+    );
+
+    match key.verify_strict(&message, signature) {
+        Ok(()) => Provenance::Verified(key_id.clone()),
+        Err(_) => Provenance::Invalid(key_id.clone()),
+    }
 }
 
-pub fn partitions(database: &Database) -> Result<Partitions, Box<dyn Error>> {
-    Ok(Partitions::new(
-        data::partition(database)?,
-        index::partition(database)?,
-        reference::partition(database)?,
-    ))
+fn validate_key_id(key_id: &str) -> Result<(), String> {
+    if u8::try_from(key_id.len()).is_err() {
+        return Err(format!("signing key id exceeds {} bytes", u8::MAX));
+    }
+
+    Ok(())
 }
 
-// -------------------------------------------------------------------------------------------------
+fn message<T>(key_id: &str, identifier: &str, version: u8, tags: T, data: &[u8]) -> Vec<u8>
+where
+    T: ExactSizeIterator,
+    T::Item: AsRef<str>,
+{
+    let mut message = Vec::new();
 
-// Insertion
+    message.put_slice(SIGNATURE_CONTEXT);
+    message.put_u64(key_id.len() as u64);
+    message.put_slice(key_id.as_bytes());
+    message.put_u64(identifier.len() as u64);
+    message.put_slice(identifier.as_bytes());
+    message.put_u8(version);
+    message.put_u64(tags.len() as u64);
+
+    for tag in tags {
+        let tag = tag.as_ref();
 
-pub fn insert(write: &mut Write<'_>, position: Position, event: Event) {
-    let event = event.into();
+        message.put_u64(tag.len() as u64);
+        message.put_slice(tag.as_bytes());
+    }
+
+    message.put_slice(data);
 
-    data::insert(write, position, &event);
-    index::insert(write, position, &event);
-    reference::insert(write, &event);
+    message
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -132,15 +1566,42 @@ pub struct HashedEvent {
     #[new(into)]
     descriptor: HashedDescriptor,
     tags: Vec<HashedTag>,
+    indexes: Vec<(u64, IndexKey)>,
+    #[new(default)]
+    signature: Option<(String, Signature)>,
 }
 
-impl From<Event> for HashedEvent {
-    fn from(event: Event) -> Self {
-        Self::new(
+impl Hasher {
+    pub fn event(&self, event: Event, extractors: &BTreeMap<String, Extractor>) -> HashedEvent {
+        let indexes = self.extract(&event, extractors);
+
+        let mut hashed = HashedEvent::new(
             event.data,
-            event.descriptor,
-            event.tags.into_iter().map(Into::into).collect(),
-        )
+            self.descriptor(event.descriptor),
+            event.tags.into_iter().map(|tag| self.tag(tag)).collect(),
+            indexes,
+        );
+
+        hashed.signature = event.signature;
+        hashed
+    }
+
+    fn extract(
+        &self,
+        event: &Event,
+        extractors: &BTreeMap<String, Extractor>,
+    ) -> Vec<(u64, IndexKey)> {
+        extractors
+            .iter()
+            .flat_map(|(name, extractor)| {
+                let name = self.hash(name);
+
+                extractor
+                    .extract(event)
+                    .into_iter()
+                    .map(move |key| (name, key))
+            })
+            .collect()
     }
 }
 
@@ -164,19 +1625,19 @@ impl HashedDescriptor {
     }
 }
 
-impl From<Descriptor> for HashedDescriptor {
-    fn from(descriptor: Descriptor) -> Self {
+impl Hasher {
+    fn descriptor(&self, descriptor: Descriptor) -> HashedDescriptor {
         let descriptor = descriptor.take();
-        let identifier = descriptor.0.into();
+        let identifier = self.identifier(descriptor.0);
         let version = descriptor.1;
 
-        Self::new(identifier, version)
+        HashedDescriptor::new(identifier, version)
     }
 }
 
 // Identifier
 
-#[derive(new, Debug)]
+#[derive(new, Clone, Debug)]
 #[new(vis())]
 pub struct HashedIdentifier(u64, Identifier);
 
@@ -194,10 +1655,10 @@ impl Deref for HashedIdentifier {
     }
 }
 
-impl From<Identifier> for HashedIdentifier {
-    fn from(descriptor_identifier: Identifier) -> Self {
-        Self::new(
-            v3::rapidhash_v3_seeded(descriptor_identifier.value().as_bytes(), &SEED),
+impl Hasher {
+    fn identifier(&self, descriptor_identifier: Identifier) -> HashedIdentifier {
+        HashedIdentifier::new(
+            self.hash(descriptor_identifier.value()),
             descriptor_identifier,
         )
     }
@@ -205,7 +1666,7 @@ impl From<Identifier> for HashedIdentifier {
 
 // Specifier
 
-#[derive(new, Debug)]
+#[derive(new, Clone, Debug)]
 #[new(vis())]
 pub struct HashedSpecifier(HashedIdentifier, Option<Range<Version>>);
 
@@ -217,15 +1678,182 @@ impl HashedSpecifier {
     fn range(&self) -> Option<&Range<Version>> {
         self.1.as_ref()
     }
+
+    fn matches(&self, descriptor: &HashedDescriptor) -> bool {
+        self.identifer().hash() == descriptor.identifer().hash()
+            && self
+                .range()
+                .is_none_or(|range| range.contains(descriptor.version()))
+    }
 }
 
-impl From<Specifier> for HashedSpecifier {
-    fn from(descriptor_specifier: Specifier) -> Self {
+impl Hasher {
+    fn specifier(&self, descriptor_specifier: Specifier) -> HashedSpecifier {
         let descriptor_specifier = descriptor_specifier.take();
-        let identifier = descriptor_specifier.0.into();
+        let identifier = self.identifier(descriptor_specifier.0);
         let range = descriptor_specifier.1;
 
-        Self::new(identifier, range)
+        HashedSpecifier::new(identifier, range)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Hashed Query
+
+// Query
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct HashedQuery(Vec<HashedSelector>);
+
+impl HashedQuery {
+    fn selectors(&self) -> &[HashedSelector] {
+        &self.0
+    }
+
+    fn is_unrestricted(&self) -> bool {
+        self.selectors().iter().any(HashedSelector::is_unrestricted)
+    }
+
+    fn is_keyed(&self) -> bool {
+        self.selectors().iter().any(|selector| {
+            selector
+                .tag_keys()
+                .iter()
+                .any(|tag_key| tag_key.values().is_empty())
+        })
+    }
+
+    fn is_prefixed(&self) -> bool {
+        self.selectors()
+            .iter()
+            .any(|selector| !selector.tag_prefixes().is_empty())
+    }
+
+    fn index_ranges(&self) -> impl Iterator<Item = &HashedIndexRange> {
+        self.selectors()
+            .iter()
+            .flat_map(HashedSelector::index_ranges)
+    }
+
+    pub fn matches(&self, event: &HashedEvent) -> bool {
+        self.selectors()
+            .iter()
+            .any(|selector| selector.matches(event))
+    }
+}
+
+impl Hasher {
+    pub fn query(&self, query: Query) -> HashedQuery {
+        HashedQuery::new(
+            query
+                .take()
+                .into_iter()
+                .map(|selector| self.selector(selector))
+                .collect(),
+        )
+    }
+}
+
+// Selector
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct HashedSelector(
+    Vec<HashedSpecifier>,
+    Vec<HashedTag>,
+    Vec<String>,
+    Vec<HashedTagKey>,
+    Vec<HashedIndexRange>,
+);
+
+impl HashedSelector {
+    fn specifiers(&self) -> &[HashedSpecifier] {
+        &self.0
+    }
+
+    fn tags(&self) -> &[HashedTag] {
+        &self.1
+    }
+
+    fn tag_prefixes(&self) -> &[String] {
+        &self.2
+    }
+
+    fn tag_keys(&self) -> &[HashedTagKey] {
+        &self.3
+    }
+
+    fn index_ranges(&self) -> &[HashedIndexRange] {
+        &self.4
+    }
+
+    fn is_unrestricted(&self) -> bool {
+        self.specifiers().is_empty()
+            && self.tags().is_empty()
+            && self.tag_prefixes().is_empty()
+            && self.tag_keys().is_empty()
+            && self.index_ranges().is_empty()
+    }
+
+    fn matches(&self, event: &HashedEvent) -> bool {
+        let specified = self.specifiers().is_empty()
+            || self
+                .specifiers()
+                .iter()
+                .any(|specifier| specifier.matches(&event.descriptor));
+
+        let tagged = self
+            .tags()
+            .iter()
+            .all(|tag| event.tags.iter().any(|other| other.hash() == tag.hash()));
+
+        let prefixed = self.tag_prefixes().iter().all(|prefix| {
+            event
+                .tags
+                .iter()
+                .any(|tag| tag.value().starts_with(prefix.as_str()))
+        });
+
+        let keyed = self
+            .tag_keys()
+            .iter()
+            .all(|tag_key| event.tags.iter().any(|tag| tag_key.matches(tag)));
+
+        let indexed = self.index_ranges().iter().all(|range| {
+            event
+                .indexes
+                .iter()
+                .any(|(name, key)| *name == range.hash() && range.contains(key))
+        });
+
+        specified && tagged && prefixed && keyed && indexed
+    }
+}
+
+impl Hasher {
+    fn selector(&self, selector: Selector) -> HashedSelector {
+        let selector = selector.take();
+        let specifiers = selector
+            .0
+            .into_iter()
+            .map(|specifier| self.specifier(specifier))
+            .collect();
+        let tags = selector.1.into_iter().map(|tag| self.tag(tag)).collect();
+        let tag_keys = selector
+            .3
+            .into_iter()
+            .map(|tag_key| self.tag_key(&tag_key))
+            .collect();
+
+        let index_ranges = selector
+            .4
+            .into_iter()
+            .map(|range| self.index_range(range))
+            .collect();
+
+        HashedSelector::new(specifiers, tags, selector.2, tag_keys, index_ranges)
     }
 }
 
@@ -235,26 +1863,511 @@ impl From<Specifier> for HashedSpecifier {
 
 // Tag
 
-#[derive(new, Debug)]
+#[derive(new, Clone, Debug)]
 #[new(vis())]
-pub struct HashedTag(u64, Tag);
+pub struct HashedTag(u64, Option<u64>, Tag);
 
 impl HashedTag {
     fn hash(&self) -> u64 {
         self.0
     }
+
+    fn key_hash(&self) -> Option<u64> {
+        self.1
+    }
 }
 
 impl Deref for HashedTag {
     type Target = Tag;
 
+    fn deref(&self) -> &Self::Target {
+        &self.2
+    }
+}
+
+impl Hasher {
+    fn tag(&self, tag: Tag) -> HashedTag {
+        let hash = self.hash(&tag.encode());
+        let key_hash = tag.key().map(|key| self.hash(key));
+
+        HashedTag::new(hash, key_hash, tag)
+    }
+}
+
+// Tag Key
+
+#[derive(new, Clone, Debug)]
+#[new(vis())]
+pub struct HashedTagKey(u64, Vec<HashedTag>, String);
+
+impl HashedTagKey {
+    fn hash(&self) -> u64 {
+        self.0
+    }
+
+    fn values(&self) -> &[HashedTag] {
+        &self.1
+    }
+
+    fn key(&self) -> &str {
+        &self.2
+    }
+
+    fn matches(&self, tag: &HashedTag) -> bool {
+        if self.values().is_empty() {
+            tag.key_hash() == Some(self.hash())
+        } else {
+            self.values().iter().any(|value| value.hash() == tag.hash())
+        }
+    }
+}
+
+impl Hasher {
+    fn tag_key(&self, tag_key: &TagKey) -> HashedTagKey {
+        let hash = self.hash(tag_key.key());
+        let values = tag_key.tags().map(|tag| self.tag(tag)).collect();
+
+        HashedTagKey::new(hash, values, tag_key.key().to_owned())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Hashed Index Range
+
+#[derive(new, Clone, Debug)]
+#[new(vis())]
+pub struct HashedIndexRange(u64, IndexRange);
+
+impl HashedIndexRange {
+    fn hash(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Deref for HashedIndexRange {
+    type Target = IndexRange;
+
     fn deref(&self) -> &Self::Target {
         &self.1
     }
 }
 
-impl From<Tag> for HashedTag {
-    fn from(tag: Tag) -> Self {
-        Self::new(v3::rapidhash_v3_seeded(tag.value().as_bytes(), &SEED), tag)
+impl Hasher {
+    fn index_range(&self, range: IndexRange) -> HashedIndexRange {
+        HashedIndexRange::new(self.hash(range.name()), range)
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        error::Error,
+        fs,
+        process,
+    };
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+    use ed25519_dalek::SigningKey;
+
+    use crate::{
+        model::{
+            Event,
+            Identifier,
+            Position,
+            Provenance,
+            Query,
+            Stream,
+            Tag,
+        },
+        options::{
+            Durability,
+            PartitionOptions,
+            Quota,
+            QuotaLimit,
+            StreamOptions,
+        },
+        persistence::{
+            self,
+            Database,
+            QuotaExceeded,
+            Read,
+            StreamOpen,
+            Unverified,
+            backend::{
+                Backend as _,
+                MemoryBackend,
+                Operation,
+            },
+            data,
+            metadata,
+        },
+    };
+
+    static EXTENDED: u8 = u8::MAX - 1;
+    static TAGS_LEN: u8 = u8::MAX - 1;
+
+    fn legacy(position: u64, migrated: bool) -> Operation {
+        let mut value = Vec::new();
+
+        value.put_u64(position);
+        value.put_u8(0);
+
+        if migrated {
+            value.put_u8(EXTENDED);
+            value.put_u8(0);
+        }
+
+        value.put_u8(TAGS_LEN);
+
+        for tag in 0..u64::from(TAGS_LEN) {
+            value.put_u64(tag);
+        }
+
+        value.put_slice(b"data");
+
+        Operation::Insert {
+            partition: data::PARTITION_NAME.into(),
+            key: position.to_be_bytes()[..].into(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn format_resumes_partial_migration() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+
+        backend.open_partition(data::PARTITION_NAME, &PartitionOptions::default())?;
+        backend.open_partition(metadata::PARTITION_NAME, &PartitionOptions::default())?;
+        backend.commit(
+            vec![legacy(0, true), legacy(1, false), Operation::Insert {
+                partition: metadata::PARTITION_NAME.into(),
+                key: b"migrated"[..].into(),
+                value: 0u64.to_be_bytes()[..].into(),
+            }],
+            Durability::Sync,
+        )?;
+
+        let database = Database::with_backend(backend.clone(), StreamOptions::default());
+        let mut partitions = persistence::partitions(&database, None)?;
+
+        persistence::format(&database, &mut partitions)?;
+
+        let read = Read::new(&partitions);
+
+        assert_eq!(metadata::format(&read)?, Some(data::FORMAT_VERSION));
+        assert_eq!(metadata::migrated(&read)?, None);
+
+        for key_value in &partitions.data {
+            let (_, value) = key_value?;
+            let mut value = value.as_ref();
+
+            value.advance(size_of::<u64>() + 1);
+
+            assert_eq!(value.get_u8(), EXTENDED);
+            assert_eq!(value.get_u8(), 0);
+            assert_eq!(value.get_u8(), TAGS_LEN);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_cuts_references_at_position() -> Result<(), Box<dyn Error>> {
+        let root = env::temp_dir().join(format!("esdb-restore-{}", process::id()));
+
+        fs::remove_dir_all(&root).ok();
+
+        let mut stream = Stream::open(
+            root.join("source"),
+            StreamOptions::default().blob_threshold(4),
+        )?;
+
+        stream.append([
+            Event::new(b"placed".to_vec(), ("order-placed", 0), [Tag::from(
+                "order:1",
+            )]),
+            Event::new(b"shipped".to_vec(), ("order-shipped", 0), [
+                Tag::from("order:1"),
+                Tag::keyed("shipment", "1"),
+            ]),
+        ])?;
+        stream.backup(root.join("backup"))?;
+
+        drop(stream);
+
+        persistence::restore(
+            root.join("backup"),
+            root.join("restored"),
+            Some(Position::from(1u64)),
+        )?;
+
+        let restored = Stream::new(root.join("restored"))?;
+        let stats = restored.stats()?;
+
+        assert_eq!(restored.event_types()?, [Identifier::from("order-placed")]);
+        assert_eq!(restored.tags("")?, [Tag::from("order:1")]);
+        assert_eq!(restored.tags("shipment:")?, []);
+        assert_eq!(stats.events(), 1);
+        assert_eq!(stats.distinct_identifiers(), 1);
+        assert_eq!(stats.distinct_tags(), 1);
+
+        let payloads = restored
+            .query(Query::all(), None)
+            .map(|event| event.map(|event| event.event.data))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(payloads, [b"placed"]);
+
+        drop(restored);
+
+        fs::remove_dir_all(root)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_streams_create_no_partitions() {
+        let backend = MemoryBackend::default();
+        let options = StreamOptions::default().create_if_missing(false);
+        let database = Database::with_backend(backend.clone(), options);
+
+        assert!(database.stream("typo").is_err());
+        assert_eq!(backend.partitions(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn blob_partitions_require_threshold() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+        let database = Database::with_backend(backend.clone(), StreamOptions::default());
+
+        database.stream("plain")?;
+
+        let database = Database::with_backend(
+            backend.clone(),
+            StreamOptions::default().blob_threshold(1024),
+        );
+
+        database.stream("offloaded")?;
+
+        assert!(!backend.partition_exists("plain#blob"));
+        assert!(backend.partition_exists("offloaded#blob"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn signatures_from_trusted_keys_are_verified() -> Result<(), Box<dyn Error>> {
+        let key = SigningKey::from_bytes(&[1; 32]);
+        let options = StreamOptions::default().trusted_key("producer", key.verifying_key());
+        let mut stream = Stream::memory(options)?;
+
+        let mut signed = Event::new(b"placed".to_vec(), ("order-placed", 0), [Tag::from(
+            "order:1",
+        )]);
+
+        signed.sign("producer", &key)?;
+
+        stream.append([
+            signed,
+            Event::new(b"placed".to_vec(), ("order-placed", 0), []),
+        ])?;
+
+        let provenances = stream
+            .query(Query::all(), None)
+            .map(|event| event.map(|event| event.provenance))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(provenances, [
+            Provenance::Verified("producer".to_owned()),
+            Provenance::Unsigned
+        ]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn signatures_from_untrusted_keys_are_rejected() -> Result<(), Box<dyn Error>> {
+        let trusted = SigningKey::from_bytes(&[1; 32]);
+        let untrusted = SigningKey::from_bytes(&[2; 32]);
+        let options = StreamOptions::default().trusted_key("producer", trusted.verifying_key());
+        let mut stream = Stream::memory(options)?;
+
+        let unverified = |result: Result<(), Box<dyn Error>>| {
+            result
+                .err()
+                .and_then(|err| err.downcast::<Unverified>().ok())
+                .map(|unverified| (unverified.key_id().to_owned(), unverified.is_trusted()))
+        };
+
+        let mut unknown = Event::new(b"placed".to_vec(), ("order-placed", 0), []);
+
+        unknown.sign("unknown", &trusted)?;
+
+        assert_eq!(
+            unverified(stream.append([unknown])),
+            Some(("unknown".to_owned(), false))
+        );
+
+        let mut forged = Event::new(b"placed".to_vec(), ("order-placed", 0), []);
+
+        forged.sign("producer", &untrusted)?;
+
+        assert_eq!(
+            unverified(stream.append([forged])),
+            Some(("producer".to_owned(), true))
+        );
+
+        let mut altered = Event::new(b"placed".to_vec(), ("order-placed", 0), []);
+
+        altered.sign("producer", &trusted)?;
+        altered.data = b"cancelled".to_vec();
+
+        assert_eq!(
+            unverified(stream.append([altered])),
+            Some(("producer".to_owned(), true))
+        );
+        assert!(stream.is_empty()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn quotas_reject_callers_within_one_group() -> Result<(), Box<dyn Error>> {
+        let quota = Quota::default().daily_bytes(10);
+        let options = StreamOptions::default()
+            .quota("tenant:a", quota.clone())
+            .quota("tenant:b", quota);
+        let mut stream = Stream::memory(options)?;
+
+        let append = |tenant: &str, data: &[u8]| {
+            let event = Event::new(data.to_vec(), ("order-placed", 0), [Tag::from(tenant)]);
+
+            (vec![event], None)
+        };
+
+        let results = stream.append_group([
+            append("tenant:a", b"placed"),
+            append("tenant:b", b"placed"),
+            append("tenant:a", b"placed"),
+            append("tenant:b", b"paid"),
+        ])?;
+
+        let exceeded = results
+            .iter()
+            .map(|result| {
+                result.as_ref().err().map(|err| {
+                    err.downcast_ref::<QuotaExceeded>()
+                        .map(|exceeded| (exceeded.namespace().to_owned(), exceeded.limit()))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(exceeded, [
+            None,
+            None,
+            Some(Some(("tenant:a".to_owned(), QuotaLimit::DailyBytes))),
+            None,
+        ]);
+        assert_eq!(stream.len()?, 3);
+
+        let results =
+            stream.append_group([append("tenant:a", b"paid"), append("tenant:b", b"x")])?;
+
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn streams_are_listed_isolated_and_dropped() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+        let database = Database::with_backend(backend.clone(), StreamOptions::default());
+        let event = || Event::new(b"placed".to_vec(), ("order-placed", 0), []);
+
+        let mut orders = database.stream("orders")?;
+        let mut billing = database.stream("billing")?;
+
+        orders.append([event(), event()])?;
+        billing.append([event()])?;
+
+        assert_eq!(database.streams(), ["billing", "orders"]);
+        assert_eq!(orders.len()?, 2);
+        assert_eq!(billing.len()?, 1);
+
+        assert!(
+            database
+                .stream("orders")
+                .is_err_and(|err| err.is::<StreamOpen>())
+        );
+        assert!(
+            database
+                .drop_stream("orders")
+                .is_err_and(|err| err.is::<StreamOpen>())
+        );
+        assert!(database.drop_stream("../orders").is_err());
+
+        drop(orders);
+
+        database.drop_stream("orders")?;
+
+        assert_eq!(database.streams(), ["billing"]);
+        assert!(
+            backend
+                .partitions()
+                .iter()
+                .all(|name| !name.starts_with("orders#"))
+        );
+        assert_eq!(billing.len()?, 1);
+        assert!(database.stream("orders")?.is_empty()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn stats_count_events_identifiers_and_tags() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+        let options = StreamOptions::default().quota("tenant", Quota::default());
+        let mut stream = Stream::with_backend(backend.clone(), options.clone())?;
+
+        stream.append([
+            Event::new(b"placed".to_vec(), ("order-placed", 0), [Tag::from(
+                "tenant:a",
+            )]),
+            Event::new(b"placed".to_vec(), ("order-placed", 0), [Tag::from(
+                "tenant:b",
+            )]),
+            Event::new(b"paid".to_vec(), ("order-paid", 0), [Tag::from("tenant:a")]),
+        ])?;
+
+        drop(stream);
+
+        let stream = Stream::with_backend(backend, options)?;
+        let stats = stream.stats()?;
+
+        assert_eq!(stats.events(), 3);
+        assert_eq!(stats.distinct_identifiers(), 2);
+        assert_eq!(stats.distinct_tags(), 2);
+        assert_eq!(stats.identifiers().iter().collect::<Vec<_>>(), [
+            (&Identifier::from("order-paid"), &1),
+            (&Identifier::from("order-placed"), &2),
+        ]);
+        assert_eq!(stats.tags().iter().collect::<Vec<_>>(), [
+            (&Tag::from("tenant:a"), &2),
+            (&Tag::from("tenant:b"), &1)
+        ]);
+        assert_eq!(stats.usage()["tenant"].storage_bytes(), 16);
+        assert!(stats.partitions().contains_key("data"));
+
+        Ok(())
     }
 }
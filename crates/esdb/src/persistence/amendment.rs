@@ -0,0 +1,269 @@
+use std::error::Error;
+
+use crate::{
+    model::Position,
+    persistence::{
+        self,
+        Amendment,
+        Database,
+        Read,
+        Write,
+        backend::{
+            KvPair,
+            PartitionHandle,
+        },
+    },
+};
+
+// =================================================================================================
+// Amendment
+// =================================================================================================
+
+pub(crate) static PARTITION_NAME: &str = "amendment";
+
+// Partition
+
+pub fn partition(
+    database: &Database,
+    stream: Option<&str>,
+) -> Result<Option<PartitionHandle>, Box<dyn Error>> {
+    persistence::optional_partition(
+        database,
+        stream,
+        PARTITION_NAME,
+        &database.options().reference,
+        true,
+    )
+}
+
+// Backup
+
+pub fn backup(
+    partition: &PartitionHandle,
+    position: Option<Position>,
+) -> impl Iterator<Item = Result<KvPair, Box<dyn Error>>> + use<> {
+    links::backup(partition, position)
+}
+
+// Retrieval
+
+pub fn all(read: &Read<'_>) -> Result<Vec<Amendment>, Box<dyn Error>> {
+    links::all(read)
+}
+
+pub fn head(read: &Read<'_>) -> Result<(u64, [u8; 32]), Box<dyn Error>> {
+    links::head(read)
+}
+
+pub fn hash(read: &Read<'_>, len: u64) -> Result<[u8; 32], Box<dyn Error>> {
+    links::hash(read, len)
+}
+
+// Insertion
+
+pub fn insert(
+    write: &mut Write<'_>,
+    position: Position,
+    previous: [u8; 32],
+    current: [u8; 32],
+) -> Result<(), Box<dyn Error>> {
+    links::insert(write, position, previous, current)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Links
+
+mod links {
+    use std::error::Error;
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+    use sha2::{
+        Digest as _,
+        Sha256,
+    };
+
+    use crate::{
+        model::Position,
+        persistence::{
+            Amendment,
+            Partitions,
+            Read,
+            Tampered,
+            Write,
+            backend::{
+                KvPair,
+                PartitionHandle,
+                Slice,
+            },
+        },
+    };
+
+    static GENESIS: [u8; 32] = [0; 32];
+    static HASH_LEN: usize = 32;
+    static VALUE_LEN: usize = size_of::<u64>() + HASH_LEN * 3;
+
+    // Backup
+
+    pub fn backup(
+        partition: &PartitionHandle,
+        position: Option<Position>,
+    ) -> impl Iterator<Item = Result<KvPair, Box<dyn Error>>> + use<> {
+        let mut head = (0, GENESIS);
+
+        partition.iter().filter_map(move |key_value| {
+            let amendment = key_value.and_then(|(_, value)| {
+                read_value(&value).ok_or_else(|| "invalid amendment entry".into())
+            });
+
+            match amendment {
+                Ok((amendment, _)) if position.is_some_and(|end| amendment.position >= end) => None,
+                Ok((amendment, _)) => {
+                    let (sequence, hash) = head;
+                    let hash = link(&hash, sequence, &amendment);
+
+                    head = (sequence + 1, hash);
+
+                    Some(Ok((
+                        Slice::from(&sequence.to_be_bytes()[..]),
+                        Slice::from(value(&amendment, &hash)),
+                    )))
+                }
+                Err(err) => Some(Err(err)),
+            }
+        })
+    }
+
+    // Retrieval
+
+    pub fn all(read: &Read<'_>) -> Result<Vec<Amendment>, Box<dyn Error>> {
+        let Some(partition) = &read.partitions.amendment else {
+            return Ok(Vec::new());
+        };
+
+        let mut hash = GENESIS;
+        let mut amendments = Vec::new();
+
+        for (sequence, key_value) in (0u64..).zip(partition.iter()) {
+            let (key, value) = key_value?;
+            let (amendment, stored) = read_value(&value).ok_or("invalid amendment entry")?;
+
+            if key.as_ref().get_u64() != sequence {
+                return Err(Tampered::new(amendment.position).into());
+            }
+
+            hash = link(&hash, sequence, &amendment);
+
+            if hash != stored {
+                return Err(Tampered::new(amendment.position).into());
+            }
+
+            amendments.push(amendment);
+        }
+
+        Ok(amendments)
+    }
+
+    pub fn head(read: &Read<'_>) -> Result<(u64, [u8; 32]), Box<dyn Error>> {
+        let Some(partition) = &read.partitions.amendment else {
+            return Ok((0, GENESIS));
+        };
+
+        match partition.last_key_value()? {
+            Some((key, value)) => {
+                let (_, hash) = read_value(&value).ok_or("invalid amendment entry")?;
+
+                Ok((key.as_ref().get_u64() + 1, hash))
+            }
+            None => Ok((0, GENESIS)),
+        }
+    }
+
+    pub fn hash(read: &Read<'_>, len: u64) -> Result<[u8; 32], Box<dyn Error>> {
+        let Some(sequence) = len.checked_sub(1) else {
+            return Ok(GENESIS);
+        };
+
+        let value = amendment(read.partitions)?
+            .get(sequence.to_be_bytes())?
+            .ok_or("missing amendment entry")?;
+        let (_, hash) = read_value(&value).ok_or("invalid amendment entry")?;
+
+        Ok(hash)
+    }
+
+    // Insertion
+
+    pub fn insert(
+        write: &mut Write<'_>,
+        position: Position,
+        previous: [u8; 32],
+        current: [u8; 32],
+    ) -> Result<(), Box<dyn Error>> {
+        let partition = amendment(write.partitions)?;
+
+        let (sequence, hash) = match write.amended {
+            Some(head) => head,
+            None => head(&Read::new(write.partitions))?,
+        };
+
+        let amendment = Amendment::new(position, previous, current);
+        let hash = link(&hash, sequence, &amendment);
+
+        write
+            .batch
+            .insert(partition, sequence.to_be_bytes(), value(&amendment, &hash));
+        write.amended = Some((sequence + 1, hash));
+
+        Ok(())
+    }
+
+    // Hashes
+
+    fn link(previous: &[u8; 32], sequence: u64, amendment: &Amendment) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+
+        hasher.update(previous);
+        hasher.update(sequence.to_be_bytes());
+        hasher.update(amendment.position.value().to_be_bytes());
+        hasher.update(amendment.previous);
+        hasher.update(amendment.current);
+        hasher.finalize().into()
+    }
+
+    // Entries
+
+    fn amendment(partitions: &Partitions) -> Result<&PartitionHandle, Box<dyn Error>> {
+        partitions
+            .amendment
+            .as_ref()
+            .ok_or_else(|| "amendment partition unavailable".into())
+    }
+
+    fn read_value(mut value: &[u8]) -> Option<(Amendment, [u8; 32])> {
+        if value.len() != VALUE_LEN {
+            return None;
+        }
+
+        let position = Position::from(value.get_u64());
+        let previous = value[..HASH_LEN].try_into().ok()?;
+        let current = value[HASH_LEN..HASH_LEN * 2].try_into().ok()?;
+        let hash = value[HASH_LEN * 2..].try_into().ok()?;
+
+        Some((Amendment::new(position, previous, current), hash))
+    }
+
+    fn value(amendment: &Amendment, hash: &[u8; 32]) -> Vec<u8> {
+        let mut value = Vec::with_capacity(VALUE_LEN);
+
+        value.put_u64(amendment.position.value());
+        value.put_slice(&amendment.previous);
+        value.put_slice(&amendment.current);
+        value.put_slice(hash);
+
+        value
+    }
+}
@@ -0,0 +1,289 @@
+use std::error::Error;
+
+use crate::{
+    model::{
+        AuditEntry,
+        Position,
+        Redaction,
+    },
+    persistence::{
+        self,
+        Database,
+        Read,
+        Write,
+        backend::{
+            KeyValues,
+            PartitionHandle,
+        },
+    },
+};
+
+// =================================================================================================
+// Audit
+// =================================================================================================
+
+pub(crate) static PARTITION_NAME: &str = "audit";
+
+// Partition
+
+pub fn partition(
+    database: &Database,
+    stream: Option<&str>,
+) -> Result<Option<PartitionHandle>, Box<dyn Error>> {
+    persistence::optional_partition(
+        database,
+        stream,
+        PARTITION_NAME,
+        &database.options().reference,
+        true,
+    )
+}
+
+// Backup
+
+pub fn backup(partition: &PartitionHandle, position: Option<Position>) -> KeyValues {
+    match position {
+        Some(position) => partition.range(..position.value().to_be_bytes()),
+        None => partition.iter(),
+    }
+}
+
+// Retrieval
+
+pub fn all(read: &Read<'_>) -> Result<Vec<AuditEntry>, Box<dyn Error>> {
+    redactions::all(read)
+}
+
+// Insertion
+
+pub fn insert(
+    write: &mut Write<'_>,
+    position: Position,
+    redaction: &Redaction,
+    timestamp: u64,
+) -> Result<(), Box<dyn Error>> {
+    redactions::insert(write, position, redaction, timestamp)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Redactions
+
+mod redactions {
+    use std::{
+        error::Error,
+        time::{
+            Duration,
+            UNIX_EPOCH,
+        },
+    };
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+
+    use crate::{
+        model::{
+            AuditEntry,
+            Position,
+            Redaction,
+        },
+        persistence::{
+            POSITION_LEN,
+            Read,
+            Write,
+        },
+    };
+
+    static KEY_LEN: usize = POSITION_LEN + SEQUENCE_LEN + TIMESTAMP_LEN;
+    static SEQUENCE_LEN: usize = size_of::<u64>();
+    static TIMESTAMP_LEN: usize = size_of::<u64>();
+
+    // Retrieval
+
+    pub fn all(read: &Read<'_>) -> Result<Vec<AuditEntry>, Box<dyn Error>> {
+        let Some(audit) = &read.partitions.audit else {
+            return Ok(Vec::new());
+        };
+
+        audit
+            .iter()
+            .map(|key_value| {
+                let (key, value) = key_value?;
+
+                read_entry(&key, &value)
+            })
+            .collect()
+    }
+
+    // Insertion
+
+    pub fn insert(
+        write: &mut Write<'_>,
+        position: Position,
+        redaction: &Redaction,
+        timestamp: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let audit = write
+            .partitions
+            .audit
+            .as_ref()
+            .ok_or("audit partition unavailable")?;
+
+        let sequence = match audit.prefix(position.value().to_be_bytes()).last() {
+            Some(key_value) => {
+                let (key, _) = key_value?;
+
+                (&key[POSITION_LEN..]).get_u64() + 1
+            }
+            None => 0,
+        };
+
+        let mut key = [0u8; KEY_LEN];
+
+        write_key(&mut key, position, sequence, timestamp);
+
+        let mut value = Vec::new();
+
+        write_value(&mut value, redaction);
+
+        write.batch.insert(audit, key, value);
+
+        Ok(())
+    }
+
+    // Keys/Values
+
+    fn read_entry(mut key: &[u8], mut value: &[u8]) -> Result<AuditEntry, Box<dyn Error>> {
+        let position = key.get_u64();
+
+        key.advance(SEQUENCE_LEN);
+
+        let timestamp = key.get_u64();
+
+        let actor_len = usize::try_from(value.get_u64())?;
+        let actor = String::from_utf8(value[..actor_len].to_vec())?;
+
+        value.advance(actor_len);
+
+        let reason = String::from_utf8(value.to_vec())?;
+        let time = UNIX_EPOCH + Duration::from_millis(timestamp);
+
+        Ok(AuditEntry::new(
+            Position::from(position),
+            time,
+            actor,
+            reason,
+        ))
+    }
+
+    fn write_key(key: &mut [u8; KEY_LEN], position: Position, sequence: u64, timestamp: u64) {
+        let mut key = &mut key[..];
+
+        key.put_u64(position.value());
+        key.put_u64(sequence);
+        key.put_u64(timestamp);
+    }
+
+    fn write_value(value: &mut Vec<u8>, redaction: &Redaction) {
+        value.put_u64(redaction.actor().len() as u64);
+        value.put_slice(redaction.actor().as_bytes());
+        value.put_slice(redaction.reason().as_bytes());
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::{
+        model::{
+            Event,
+            Position,
+            Query,
+            Redaction,
+            Stream,
+            Tag,
+        },
+        options::StreamOptions,
+    };
+
+    fn stream() -> Result<Stream, Box<dyn Error>> {
+        let mut stream = Stream::memory(StreamOptions::default())?;
+
+        stream.append([
+            Event::new(b"alice".to_vec(), ("customer-registered", 0), [Tag::from(
+                "customer:1",
+            )]),
+            Event::new(b"bob".to_vec(), ("customer-registered", 0), [Tag::from(
+                "customer:2",
+            )]),
+        ])?;
+
+        Ok(stream)
+    }
+
+    #[test]
+    fn redactions_replace_data_and_record_audit_entries() -> Result<(), Box<dyn Error>> {
+        let mut stream = stream()?;
+
+        stream.redact(
+            Position::from(1u64),
+            &Redaction::new(b"[redacted]".to_vec(), "dpo", "erasure request"),
+        )?;
+
+        let data = stream
+            .query(Query::all(), None)
+            .map(|event| event.map(|event| event.event.data))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(data, [b"alice".to_vec(), b"[redacted]".to_vec()]);
+
+        let audit = stream.audit()?;
+
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].position(), Position::from(1u64));
+        assert_eq!(audit[0].actor(), "dpo");
+        assert_eq!(audit[0].reason(), "erasure request");
+
+        Ok(())
+    }
+
+    #[test]
+    fn repeated_redactions_keep_every_audit_entry() -> Result<(), Box<dyn Error>> {
+        let mut stream = stream()?;
+
+        for actor in ["first", "second", "third"] {
+            stream.redact(
+                Position::from(0u64),
+                &Redaction::new(Vec::new(), actor, "erasure request"),
+            )?;
+        }
+
+        let actors = stream
+            .audit()?
+            .iter()
+            .map(|entry| entry.actor().to_owned())
+            .collect::<Vec<_>>();
+
+        assert_eq!(actors, ["first", "second", "third"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn redactions_of_missing_events_fail() -> Result<(), Box<dyn Error>> {
+        let mut stream = stream()?;
+        let redaction = Redaction::new(Vec::new(), "dpo", "erasure request");
+
+        assert!(stream.redact(Position::from(2u64), &redaction).is_err());
+        assert!(stream.audit()?.is_empty());
+
+        Ok(())
+    }
+}
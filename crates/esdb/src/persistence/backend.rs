@@ -0,0 +1,856 @@
+use std::{
+    collections::{
+        BTreeMap,
+        VecDeque,
+    },
+    error::Error,
+    ops::{
+        Bound,
+        RangeBounds,
+    },
+    path::Path,
+    sync::{
+        Arc,
+        RwLock,
+    },
+};
+
+use derive_more::Debug;
+use fancy_constructor::new;
+use fjall::{
+    Instant,
+    Keyspace,
+    PartitionCreateOptions,
+    Snapshot,
+};
+pub use fjall::{
+    KvPair,
+    Slice,
+};
+
+use crate::{
+    options::{
+        Durability,
+        PartitionOptions,
+        StreamOptions,
+    },
+    persistence::ReadOnly,
+};
+
+// =================================================================================================
+// Backend
+// =================================================================================================
+
+pub type KeyValues = Box<dyn Iterator<Item = Result<KvPair, Box<dyn Error>>>>;
+
+pub trait Backend: Send + Sync {
+    fn open_partition(
+        &self,
+        name: &str,
+        options: &PartitionOptions,
+    ) -> Result<Arc<dyn Partition>, Box<dyn Error>>;
+
+    fn partition_exists(&self, name: &str) -> bool;
+
+    fn partitions(&self) -> Vec<String>;
+
+    fn delete_partition(&self, name: &str) -> Result<(), Box<dyn Error>>;
+
+    fn commit(
+        &self,
+        operations: Vec<Operation>,
+        durability: Durability,
+    ) -> Result<(), Box<dyn Error>>;
+
+    fn persist(&self) -> Result<(), Box<dyn Error>>;
+
+    fn snapshot(&self) -> Arc<dyn Backend>;
+}
+
+pub trait Partition: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn get(&self, key: &[u8]) -> Result<Option<Slice>, Box<dyn Error>>;
+
+    fn contains_key(&self, key: &[u8]) -> Result<bool, Box<dyn Error>> {
+        self.get(key).map(|value| value.is_some())
+    }
+
+    fn last_key_value(&self) -> Result<Option<KvPair>, Box<dyn Error>>;
+
+    fn range(&self, lower: Bound<&[u8]>, upper: Bound<&[u8]>) -> KeyValues;
+
+    fn prefix(&self, prefix: &[u8]) -> KeyValues;
+
+    fn disk_space(&self) -> u64;
+}
+
+// Operation
+
+#[derive(Clone, Debug)]
+pub enum Operation {
+    Insert {
+        partition: Arc<str>,
+        key: Slice,
+        value: Slice,
+    },
+    Remove {
+        partition: Arc<str>,
+        key: Slice,
+    },
+}
+
+impl Operation {
+    pub fn partition(&self) -> &str {
+        match self {
+            Self::Insert { partition, .. } | Self::Remove { partition, .. } => partition,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Partition Handle
+
+#[derive(Clone, Debug)]
+#[debug("PartitionHandle({name:?})")]
+pub struct PartitionHandle {
+    name: Arc<str>,
+    partition: Arc<dyn Partition>,
+}
+
+impl PartitionHandle {
+    pub(crate) fn new(partition: Arc<dyn Partition>) -> Self {
+        let name = Arc::from(partition.name());
+
+        Self { name, partition }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get<K>(&self, key: K) -> Result<Option<Slice>, Box<dyn Error>>
+    where
+        K: AsRef<[u8]>,
+    {
+        self.partition.get(key.as_ref())
+    }
+
+    pub fn contains_key<K>(&self, key: K) -> Result<bool, Box<dyn Error>>
+    where
+        K: AsRef<[u8]>,
+    {
+        self.partition.contains_key(key.as_ref())
+    }
+
+    pub fn last_key_value(&self) -> Result<Option<KvPair>, Box<dyn Error>> {
+        self.partition.last_key_value()
+    }
+
+    pub fn iter(&self) -> KeyValues {
+        self.partition.range(Bound::Unbounded, Bound::Unbounded)
+    }
+
+    pub fn range<K, R>(&self, range: R) -> KeyValues
+    where
+        K: AsRef<[u8]>,
+        R: RangeBounds<K>,
+    {
+        let lower = range.start_bound().map(AsRef::as_ref);
+        let upper = range.end_bound().map(AsRef::as_ref);
+
+        self.partition.range(lower, upper)
+    }
+
+    pub fn prefix<K>(&self, prefix: K) -> KeyValues
+    where
+        K: AsRef<[u8]>,
+    {
+        self.partition.prefix(prefix.as_ref())
+    }
+
+    pub fn disk_space(&self) -> u64 {
+        self.partition.disk_space()
+    }
+}
+
+impl IntoIterator for &PartitionHandle {
+    type IntoIter = KeyValues;
+    type Item = Result<KvPair, Box<dyn Error>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Batch
+
+#[derive(new, Debug)]
+#[must_use]
+#[new(vis(pub(crate)))]
+pub struct Batch {
+    #[debug("Backend")]
+    backend: Arc<dyn Backend>,
+    #[new(default)]
+    operations: Vec<Operation>,
+    #[new(default)]
+    durability: Durability,
+}
+
+impl Batch {
+    pub fn durability(mut self, durability: Durability) -> Self {
+        self.durability = durability;
+        self
+    }
+
+    pub fn insert<K, V>(&mut self, partition: &PartitionHandle, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        self.operations.push(Operation::Insert {
+            partition: partition.name.clone(),
+            key: Slice::from(key.as_ref()),
+            value: Slice::from(value.as_ref()),
+        });
+    }
+
+    pub fn remove<K>(&mut self, partition: &PartitionHandle, key: K)
+    where
+        K: AsRef<[u8]>,
+    {
+        self.operations.push(Operation::Remove {
+            partition: partition.name.clone(),
+            key: Slice::from(key.as_ref()),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn commit(self) -> Result<(), Box<dyn Error>> {
+        self.backend.commit(self.operations, self.durability)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Fjall Backend
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct FjallBackend {
+    #[debug("Keyspace")]
+    keyspace: Keyspace,
+    #[debug(skip)]
+    #[new(default)]
+    handles: RwLock<BTreeMap<String, fjall::PartitionHandle>>,
+}
+
+impl FjallBackend {
+    pub fn open<P>(path: P, options: &StreamOptions) -> Result<Self, Box<dyn Error>>
+    where
+        P: AsRef<Path>,
+    {
+        let config = options.database.config(path, options.durability);
+
+        Ok(config.open().map(Self::new)?)
+    }
+}
+
+impl Backend for FjallBackend {
+    fn open_partition(
+        &self,
+        name: &str,
+        options: &PartitionOptions,
+    ) -> Result<Arc<dyn Partition>, Box<dyn Error>> {
+        let handle = self.keyspace.open_partition(name, options.create())?;
+
+        self.handles
+            .write()
+            .expect("backend lock poisoned")
+            .insert(name.to_owned(), handle.clone());
+
+        Ok(Arc::new(FjallPartition::new(handle)))
+    }
+
+    fn partition_exists(&self, name: &str) -> bool {
+        self.keyspace.partition_exists(name)
+    }
+
+    fn partitions(&self) -> Vec<String> {
+        fjall_partitions(&self.keyspace)
+    }
+
+    fn delete_partition(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        self.handles
+            .write()
+            .expect("backend lock poisoned")
+            .remove(name);
+
+        let handle = self
+            .keyspace
+            .open_partition(name, PartitionCreateOptions::default())?;
+
+        Ok(self.keyspace.delete_partition(handle)?)
+    }
+
+    fn commit(
+        &self,
+        operations: Vec<Operation>,
+        durability: Durability,
+    ) -> Result<(), Box<dyn Error>> {
+        let handles = self.handles.read().expect("backend lock poisoned");
+        let mut batch = self.keyspace.batch().durability(durability.mode());
+
+        for operation in operations {
+            let handle = handles
+                .get(operation.partition())
+                .ok_or_else(|| format!("partition {} is not open", operation.partition()))?;
+
+            match operation {
+                Operation::Insert { key, value, .. } => batch.insert(handle, key, value),
+                Operation::Remove { key, .. } => batch.remove(handle, key),
+            }
+        }
+
+        Ok(batch.commit()?)
+    }
+
+    fn persist(&self) -> Result<(), Box<dyn Error>> {
+        Ok(self.keyspace.persist(fjall::PersistMode::SyncAll)?)
+    }
+
+    fn snapshot(&self) -> Arc<dyn Backend> {
+        Arc::new(FjallSnapshot::new(
+            self.keyspace.clone(),
+            self.keyspace.instant(),
+        ))
+    }
+}
+
+fn fjall_partitions(keyspace: &Keyspace) -> Vec<String> {
+    keyspace
+        .list_partitions()
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+// Partition
+
+#[derive(new, Debug)]
+#[new(vis())]
+struct FjallPartition {
+    #[debug("PartitionHandle({:?})", handle.name)]
+    handle: fjall::PartitionHandle,
+}
+
+impl Partition for FjallPartition {
+    fn name(&self) -> &str {
+        &self.handle.name
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<Slice>, Box<dyn Error>> {
+        Ok(self.handle.get(key)?)
+    }
+
+    fn contains_key(&self, key: &[u8]) -> Result<bool, Box<dyn Error>> {
+        Ok(self.handle.contains_key(key)?)
+    }
+
+    fn last_key_value(&self) -> Result<Option<KvPair>, Box<dyn Error>> {
+        Ok(self.handle.last_key_value()?)
+    }
+
+    fn range(&self, lower: Bound<&[u8]>, upper: Bound<&[u8]>) -> KeyValues {
+        Box::new(
+            self.handle
+                .range::<&[u8], _>((lower, upper))
+                .map(|key_value| key_value.map_err(Into::into)),
+        )
+    }
+
+    fn prefix(&self, prefix: &[u8]) -> KeyValues {
+        Box::new(
+            self.handle
+                .prefix(prefix)
+                .map(|key_value| key_value.map_err(Into::into)),
+        )
+    }
+
+    fn disk_space(&self) -> u64 {
+        self.handle.disk_space()
+    }
+}
+
+// Snapshot
+
+#[derive(new, Debug)]
+#[new(vis())]
+struct FjallSnapshot {
+    #[debug("Keyspace")]
+    keyspace: Keyspace,
+    instant: Instant,
+}
+
+impl Backend for FjallSnapshot {
+    fn open_partition(
+        &self,
+        name: &str,
+        options: &PartitionOptions,
+    ) -> Result<Arc<dyn Partition>, Box<dyn Error>> {
+        let handle = self.keyspace.open_partition(name, options.create())?;
+        let snapshot = handle.snapshot_at(self.instant);
+
+        Ok(Arc::new(FjallSnapshotPartition::new(handle, snapshot)))
+    }
+
+    fn partition_exists(&self, name: &str) -> bool {
+        self.keyspace.partition_exists(name)
+    }
+
+    fn partitions(&self) -> Vec<String> {
+        fjall_partitions(&self.keyspace)
+    }
+
+    fn delete_partition(&self, _: &str) -> Result<(), Box<dyn Error>> {
+        Err(ReadOnly.into())
+    }
+
+    fn commit(&self, _: Vec<Operation>, _: Durability) -> Result<(), Box<dyn Error>> {
+        Err(ReadOnly.into())
+    }
+
+    fn persist(&self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Arc<dyn Backend> {
+        Arc::new(Self::new(self.keyspace.clone(), self.instant))
+    }
+}
+
+#[derive(new, Debug)]
+#[new(vis())]
+struct FjallSnapshotPartition {
+    #[debug("PartitionHandle({:?})", handle.name)]
+    handle: fjall::PartitionHandle,
+    #[debug("Snapshot")]
+    snapshot: Snapshot,
+}
+
+impl Partition for FjallSnapshotPartition {
+    fn name(&self) -> &str {
+        &self.handle.name
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<Slice>, Box<dyn Error>> {
+        Ok(self.snapshot.get(key)?)
+    }
+
+    fn contains_key(&self, key: &[u8]) -> Result<bool, Box<dyn Error>> {
+        Ok(self.snapshot.contains_key(key)?)
+    }
+
+    fn last_key_value(&self) -> Result<Option<KvPair>, Box<dyn Error>> {
+        Ok(self.snapshot.last_key_value()?)
+    }
+
+    fn range(&self, lower: Bound<&[u8]>, upper: Bound<&[u8]>) -> KeyValues {
+        Box::new(
+            self.snapshot
+                .range::<&[u8], _>((lower, upper))
+                .map(|key_value| key_value.map_err(Into::into)),
+        )
+    }
+
+    fn prefix(&self, prefix: &[u8]) -> KeyValues {
+        Box::new(
+            self.snapshot
+                .prefix(prefix)
+                .map(|key_value| key_value.map_err(Into::into)),
+        )
+    }
+
+    fn disk_space(&self) -> u64 {
+        self.handle.disk_space()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Memory Backend
+
+static MEMORY_ITERATION_LEN: usize = 256;
+
+type MemoryPartitions = BTreeMap<String, Arc<MemoryEntries>>;
+type MemoryEntries = BTreeMap<Slice, Slice>;
+
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBackend {
+    #[debug("MemoryPartitions")]
+    partitions: Arc<RwLock<MemoryPartitions>>,
+}
+
+impl Backend for MemoryBackend {
+    fn open_partition(
+        &self,
+        name: &str,
+        _: &PartitionOptions,
+    ) -> Result<Arc<dyn Partition>, Box<dyn Error>> {
+        self.partitions
+            .write()
+            .expect("backend lock poisoned")
+            .entry(name.to_owned())
+            .or_default();
+
+        Ok(Arc::new(MemoryPartition::new(
+            name,
+            self.partitions.clone(),
+        )))
+    }
+
+    fn partition_exists(&self, name: &str) -> bool {
+        self.partitions
+            .read()
+            .expect("backend lock poisoned")
+            .contains_key(name)
+    }
+
+    fn partitions(&self) -> Vec<String> {
+        self.partitions
+            .read()
+            .expect("backend lock poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    fn delete_partition(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        self.partitions
+            .write()
+            .expect("backend lock poisoned")
+            .remove(name);
+
+        Ok(())
+    }
+
+    fn commit(&self, operations: Vec<Operation>, _: Durability) -> Result<(), Box<dyn Error>> {
+        let mut partitions = self.partitions.write().expect("backend lock poisoned");
+
+        if let Some(operation) = operations
+            .iter()
+            .find(|operation| !partitions.contains_key(operation.partition()))
+        {
+            return Err(format!("partition {} is not open", operation.partition()).into());
+        }
+
+        for operation in operations {
+            let partition = partitions
+                .get_mut(operation.partition())
+                .map(Arc::make_mut)
+                .expect("partition checked before commit");
+
+            match operation {
+                Operation::Insert { key, value, .. } => partition.insert(key, value),
+                Operation::Remove { key, .. } => partition.remove(&key),
+            };
+        }
+
+        Ok(())
+    }
+
+    fn persist(&self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Arc<dyn Backend> {
+        let partitions = self.partitions.read().expect("backend lock poisoned");
+
+        Arc::new(Self {
+            partitions: Arc::new(RwLock::new(partitions.clone())),
+        })
+    }
+}
+
+// Partition
+
+#[derive(new, Debug)]
+#[new(vis())]
+struct MemoryPartition {
+    #[new(into)]
+    name: Arc<str>,
+    #[debug(skip)]
+    partitions: Arc<RwLock<MemoryPartitions>>,
+}
+
+impl Partition for MemoryPartition {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<Slice>, Box<dyn Error>> {
+        let partitions = self.partitions.read().expect("backend lock poisoned");
+        let value = partitions
+            .get(&*self.name)
+            .and_then(|partition| partition.get(key))
+            .cloned();
+
+        Ok(value)
+    }
+
+    fn last_key_value(&self) -> Result<Option<KvPair>, Box<dyn Error>> {
+        let partitions = self.partitions.read().expect("backend lock poisoned");
+        let key_value = partitions
+            .get(&*self.name)
+            .and_then(|partition| partition.last_key_value())
+            .map(|(key, value)| (key.clone(), value.clone()));
+
+        Ok(key_value)
+    }
+
+    fn range(&self, lower: Bound<&[u8]>, upper: Bound<&[u8]>) -> KeyValues {
+        Box::new(MemoryIterator::new(
+            self.entries(),
+            lower.map(Slice::from),
+            upper.map(Slice::from),
+        ))
+    }
+
+    fn prefix(&self, prefix: &[u8]) -> KeyValues {
+        let upper = successor(prefix).map_or(Bound::Unbounded, Bound::Excluded);
+
+        Box::new(MemoryIterator::new(
+            self.entries(),
+            Bound::Included(Slice::from(prefix)),
+            upper,
+        ))
+    }
+
+    fn disk_space(&self) -> u64 {
+        let partitions = self.partitions.read().expect("backend lock poisoned");
+
+        partitions.get(&*self.name).map_or(0, |partition| {
+            partition
+                .iter()
+                .map(|(key, value)| (key.len() + value.len()) as u64)
+                .sum()
+        })
+    }
+}
+
+impl MemoryPartition {
+    fn entries(&self) -> Option<Arc<MemoryEntries>> {
+        let partitions = self.partitions.read().expect("backend lock poisoned");
+
+        partitions.get(&*self.name).cloned()
+    }
+}
+
+fn successor(prefix: &[u8]) -> Option<Slice> {
+    let end = prefix.iter().rposition(|byte| *byte < u8::MAX)?;
+    let mut upper = prefix[..=end].to_vec();
+
+    upper[end] += 1;
+
+    Some(Slice::from(upper))
+}
+
+// Iterator
+
+#[derive(new, Debug)]
+#[new(vis())]
+struct MemoryIterator {
+    #[debug(skip)]
+    entries: Option<Arc<MemoryEntries>>,
+    lower: Bound<Slice>,
+    upper: Bound<Slice>,
+    #[new(default)]
+    buffer: VecDeque<KvPair>,
+}
+
+impl MemoryIterator {
+    fn fill(&mut self) {
+        if is_empty(self.lower.as_ref(), self.upper.as_ref()) {
+            return;
+        }
+
+        let Some(entries) = &self.entries else {
+            return;
+        };
+
+        let range = (
+            self.lower.as_ref().map(AsRef::as_ref),
+            self.upper.as_ref().map(AsRef::as_ref),
+        );
+
+        self.buffer.extend(
+            entries
+                .range::<[u8], _>(range)
+                .take(MEMORY_ITERATION_LEN)
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+
+        if let Some((key, _)) = self.buffer.back() {
+            self.lower = Bound::Excluded(key.clone());
+        }
+    }
+}
+
+impl Iterator for MemoryIterator {
+    type Item = Result<KvPair, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buffer.is_empty() {
+            self.fill();
+        }
+
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
+fn is_empty(lower: Bound<&Slice>, upper: Bound<&Slice>) -> bool {
+    match (lower, upper) {
+        (Bound::Included(lower), Bound::Included(upper)) => lower > upper,
+        (Bound::Included(lower) | Bound::Excluded(lower), Bound::Excluded(upper))
+        | (Bound::Excluded(lower), Bound::Included(upper)) => lower >= upper,
+        _ => false,
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        error::Error,
+        ops::Bound,
+        sync::Arc,
+    };
+
+    use crate::{
+        options::{
+            Durability,
+            PartitionOptions,
+        },
+        persistence::backend::{
+            Backend,
+            MemoryBackend,
+            Operation,
+            Slice,
+        },
+    };
+
+    fn insert(
+        backend: &MemoryBackend,
+        keys: impl Iterator<Item = u64>,
+    ) -> Result<(), Box<dyn Error>> {
+        let operations = keys
+            .map(|key| Operation::Insert {
+                partition: Arc::from("data"),
+                key: Slice::from(&key.to_be_bytes()[..]),
+                value: Slice::from(&key.to_be_bytes()[..]),
+            })
+            .collect();
+
+        backend.commit(operations, Durability::default())
+    }
+
+    fn keys(values: Vec<(Slice, Slice)>) -> Vec<u64> {
+        values
+            .into_iter()
+            .map(|(key, _)| u64::from_be_bytes(key[..].try_into().expect("eight byte key")))
+            .collect()
+    }
+
+    #[test]
+    fn iterators_ignore_later_inserts() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+        let partition = backend.open_partition("data", &PartitionOptions::default())?;
+
+        insert(&backend, (0..600).map(|key| key * 2))?;
+
+        let mut iterator = partition.range(Bound::Unbounded, Bound::Unbounded);
+        let mut values = vec![iterator.next().expect("first entry")?];
+
+        insert(&backend, (0..600).map(|key| key * 2 + 1))?;
+
+        values.extend(iterator.collect::<Result<Vec<_>, _>>()?);
+
+        assert_eq!(
+            keys(values),
+            (0..600).map(|key| key * 2).collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn iterators_ignore_later_removals() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+        let partition = backend.open_partition("data", &PartitionOptions::default())?;
+
+        insert(&backend, 0..600)?;
+
+        let mut iterator = partition.prefix(&[0]);
+        let mut values = vec![iterator.next().expect("first entry")?];
+
+        let removals = (1..600)
+            .map(|key: u64| Operation::Remove {
+                partition: Arc::from("data"),
+                key: Slice::from(&key.to_be_bytes()[..]),
+            })
+            .collect();
+
+        backend.commit(removals, Durability::default())?;
+
+        values.extend(iterator.collect::<Result<Vec<_>, _>>()?);
+
+        assert_eq!(keys(values), (0..600).collect::<Vec<_>>());
+        assert_eq!(
+            partition.range(Bound::Unbounded, Bound::Unbounded).count(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn ranges_and_prefixes_are_bounded() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+        let partition = backend.open_partition("data", &PartitionOptions::default())?;
+
+        insert(&backend, 0..1000)?;
+
+        let lower = 300_u64.to_be_bytes();
+        let upper = 700_u64.to_be_bytes();
+        let range = partition
+            .range(Bound::Excluded(&lower), Bound::Included(&upper))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(keys(range), (301..=700).collect::<Vec<_>>());
+
+        let prefix = partition
+            .prefix(&[0, 0, 0, 0, 0, 0, 1])
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(keys(prefix), (256..512).collect::<Vec<_>>());
+
+        let empty = partition.range(Bound::Included(&upper), Bound::Excluded(&lower));
+
+        assert_eq!(empty.count(), 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    error::Error,
+    io,
+};
+
+use derive_more::Debug;
+use fancy_constructor::new;
+
+use crate::{
+    model::Position,
+    persistence::{
+        self,
+        Corrupted,
+        Database,
+        Read,
+        Write,
+        backend::{
+            KeyValues,
+            PartitionHandle,
+        },
+    },
+};
+
+// =================================================================================================
+// Blob
+// =================================================================================================
+
+pub(crate) static PARTITION_NAME: &str = "blob";
+
+// Partition
+
+pub fn partition(
+    database: &Database,
+    stream: Option<&str>,
+) -> Result<Option<PartitionHandle>, Box<dyn Error>> {
+    persistence::optional_partition(
+        database,
+        stream,
+        PARTITION_NAME,
+        &database.options().data,
+        database.options().blob_threshold.is_some(),
+    )
+}
+
+// Backup
+
+pub fn backup(partition: &PartitionHandle, position: Option<Position>) -> KeyValues {
+    match position {
+        Some(position) => partition.range(..position.value().to_be_bytes()),
+        None => partition.iter(),
+    }
+}
+
+// Retrieval
+
+pub fn get(read: &Read<'_>, position: Position, len: u64) -> Result<Vec<u8>, Box<dyn Error>> {
+    chunks::get(read, position, len)
+}
+
+pub fn payload(read: &Read<'_>, position: Position, len: u64) -> Result<Payload, Box<dyn Error>> {
+    chunks::payload(read, position, len)
+}
+
+// Insertion
+
+pub fn insert(
+    write: &mut Write<'_>,
+    position: Position,
+    data: &[u8],
+) -> Result<(), Box<dyn Error>> {
+    chunks::insert(write, position, data)
+}
+
+// Removal
+
+pub fn remove(write: &mut Write<'_>, position: Position, len: u64) -> Result<(), Box<dyn Error>> {
+    chunks::remove(write, position, len)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Payload
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct Payload {
+    partition: Option<PartitionHandle>,
+    position: Position,
+    len: u64,
+    #[debug(skip)]
+    buffer: Vec<u8>,
+    #[new(default)]
+    chunk: u32,
+    #[new(default)]
+    loaded: u64,
+    #[new(default)]
+    offset: usize,
+}
+
+impl Payload {
+    pub(crate) fn inline(position: Position, data: Vec<u8>) -> Self {
+        Self::new(None, position, data.len() as u64, data)
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_offloaded(&self) -> bool {
+        self.partition.is_some()
+    }
+}
+
+impl io::Read for Payload {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.offset == self.buffer.len()
+            && self.loaded < self.len
+            && let Some(partition) = &self.partition
+        {
+            let chunk = chunks::chunk(partition, self.position, self.chunk)
+                .map_err(|err| io::Error::other(err.to_string()))?
+                .ok_or_else(|| io::Error::other(Corrupted::new(self.position)))?;
+
+            self.loaded += chunk.len() as u64;
+            self.buffer = chunk;
+            self.chunk += 1;
+            self.offset = 0;
+        }
+
+        let remaining = &self.buffer[self.offset..];
+        let len = remaining.len().min(buf.len());
+
+        buf[..len].copy_from_slice(&remaining[..len]);
+
+        self.offset += len;
+
+        Ok(len)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Chunks
+
+mod chunks {
+    use std::error::Error;
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+    use xxhash_rust::xxh3;
+
+    use crate::{
+        model::Position,
+        persistence::{
+            Corrupted,
+            POSITION_LEN,
+            Partitions,
+            Read,
+            Write,
+            backend::PartitionHandle,
+            blob::Payload,
+        },
+    };
+
+    static CHECKSUM_LEN: usize = size_of::<u64>();
+    static CHUNK_LEN: usize = 64 * 1024;
+    static INDEX_LEN: usize = size_of::<u32>();
+    static KEY_LEN: usize = POSITION_LEN + INDEX_LEN;
+
+    // Retrieval
+
+    pub fn get(read: &Read<'_>, position: Position, len: u64) -> Result<Vec<u8>, Box<dyn Error>> {
+        let blob = blob(read.partitions)?;
+        let mut data = Vec::with_capacity(usize::try_from(len)?);
+
+        for index in 0..count(len)? {
+            let chunk = chunk(blob, position, index)?.ok_or(Corrupted::new(position))?;
+
+            data.extend_from_slice(&chunk);
+        }
+
+        if data.len() as u64 == len {
+            Ok(data)
+        } else {
+            Err(Corrupted::new(position).into())
+        }
+    }
+
+    pub fn payload(
+        read: &Read<'_>,
+        position: Position,
+        len: u64,
+    ) -> Result<Payload, Box<dyn Error>> {
+        let blob = blob(read.partitions)?.clone();
+
+        Ok(Payload::new(Some(blob), position, len, Vec::new()))
+    }
+
+    pub fn chunk(
+        blob: &PartitionHandle,
+        position: Position,
+        index: u32,
+    ) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        let mut key = [0u8; KEY_LEN];
+
+        write_key(&mut key, position, index);
+
+        let Some(value) = blob.get(key)? else {
+            return Ok(None);
+        };
+
+        let chunk = verify(&value).ok_or(Corrupted::new(position))?;
+
+        Ok(Some(chunk.to_vec()))
+    }
+
+    // Insertion
+
+    pub fn insert(
+        write: &mut Write<'_>,
+        position: Position,
+        data: &[u8],
+    ) -> Result<(), Box<dyn Error>> {
+        let blob = blob(write.partitions)?;
+        let mut key = [0u8; KEY_LEN];
+
+        for (index, chunk) in (0..).zip(data.chunks(CHUNK_LEN)) {
+            write_key(&mut key, position, index);
+
+            let mut value = Vec::with_capacity(chunk.len() + CHECKSUM_LEN);
+
+            value.put_slice(chunk);
+            value.put_u64(xxh3::xxh3_64(chunk));
+
+            write.batch.insert(blob, key, value);
+        }
+
+        Ok(())
+    }
+
+    // Removal
+
+    pub fn remove(
+        write: &mut Write<'_>,
+        position: Position,
+        len: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let blob = blob(write.partitions)?;
+        let retained = count(len)?;
+
+        for key_value in blob.prefix(position.value().to_be_bytes()) {
+            let (key, _) = key_value?;
+
+            let mut index = &key[POSITION_LEN..];
+
+            if index.get_u32() >= retained {
+                write.batch.remove(blob, key);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Chunks
+
+    fn blob(partitions: &Partitions) -> Result<&PartitionHandle, Box<dyn Error>> {
+        partitions
+            .blob
+            .as_ref()
+            .ok_or_else(|| "blob partition unavailable".into())
+    }
+
+    fn count(len: u64) -> Result<u32, Box<dyn Error>> {
+        Ok(u32::try_from(len.div_ceil(CHUNK_LEN as u64))?)
+    }
+
+    fn verify(value: &[u8]) -> Option<&[u8]> {
+        let len = value.len().checked_sub(CHECKSUM_LEN)?;
+        let (value, mut checksum) = value.split_at(len);
+
+        (checksum.get_u64() == xxh3::xxh3_64(value)).then_some(value)
+    }
+
+    // Keys/Prefixes
+
+    fn write_key(key: &mut [u8; KEY_LEN], position: Position, index: u32) {
+        let mut key = &mut key[..];
+
+        key.put_u64(position.value());
+        key.put_u32(index);
+    }
+}
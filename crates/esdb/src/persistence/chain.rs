@@ -0,0 +1,660 @@
+use std::error::Error;
+
+use crate::{
+    model::{
+        Position,
+        Verification,
+    },
+    persistence::{
+        self,
+        Database,
+        HashedEvent,
+        Partitions,
+        Read,
+        Write,
+        backend::{
+            KeyValues,
+            PartitionHandle,
+        },
+    },
+};
+
+// =================================================================================================
+// Chain
+// =================================================================================================
+
+pub(crate) static PARTITION_NAME: &str = "chain";
+
+// Partition
+
+pub fn partition(
+    database: &Database,
+    stream: Option<&str>,
+) -> Result<Option<PartitionHandle>, Box<dyn Error>> {
+    persistence::optional_partition(
+        database,
+        stream,
+        PARTITION_NAME,
+        &database.options().reference,
+        true,
+    )
+}
+
+// Backup
+
+pub fn backup(partition: &PartitionHandle, position: Option<Position>) -> KeyValues {
+    match position {
+        Some(position) => partition.range(..position.value().to_be_bytes()),
+        None => partition.iter(),
+    }
+}
+
+// Retrieval
+
+pub fn hash(read: &Read<'_>, position: Position) -> Result<[u8; 32], Box<dyn Error>> {
+    links::hash(read, position)
+}
+
+// Verification
+
+pub fn verify(
+    read: &Read<'_>,
+    start: Position,
+    end: Position,
+) -> Result<Verification, Box<dyn Error>> {
+    links::verify(read, start, end)
+}
+
+// Insertion
+
+pub fn insert(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+) -> Result<(), Box<dyn Error>> {
+    links::insert(write, position, event)
+}
+
+// Amendment
+
+pub fn redact(
+    write: &mut Write<'_>,
+    position: Position,
+    replacement: &HashedEvent,
+) -> Result<(), Box<dyn Error>> {
+    links::redact(write, position, replacement)
+}
+
+pub fn tombstone(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+    archived: u64,
+) -> Result<(), Box<dyn Error>> {
+    links::tombstone(write, position, event, archived)
+}
+
+// Maintenance
+
+pub fn backfill(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+    links::backfill(database, partitions)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Links
+
+mod links {
+    use std::{
+        collections::BTreeMap,
+        error::Error,
+    };
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+    use sha2::{
+        Digest as _,
+        Sha256,
+    };
+
+    use crate::{
+        model::{
+            Position,
+            Verification,
+        },
+        persistence::{
+            Database,
+            HashedEvent,
+            Partitions,
+            Read,
+            Record,
+            Tampered,
+            Write,
+            amendment,
+            backend::PartitionHandle,
+            data,
+            metadata,
+            reference,
+        },
+    };
+
+    static BACKFILL_BATCH_LEN: usize = 1024;
+    static HASH_LEN: usize = 32;
+    static GENESIS: [u8; 32] = [0; 32];
+
+    // Retrieval
+
+    pub fn hash(read: &Read<'_>, position: Position) -> Result<[u8; 32], Box<dyn Error>> {
+        let chain = chain(read.partitions)?;
+        let (_, hash) = get(chain, position)?;
+
+        Ok(hash)
+    }
+
+    // Verification
+
+    pub fn verify(
+        read: &Read<'_>,
+        start: Position,
+        end: Position,
+    ) -> Result<Verification, Box<dyn Error>> {
+        let chain = chain(read.partitions)?;
+        let amendments = amendments(read, chain, end)?;
+
+        let mut hash = previous(chain, start)?;
+        let mut redacted = Vec::new();
+        let mut archived = Vec::new();
+
+        for position in start.value()..end.value() {
+            let position = Position::from(position);
+            let (digest, stored) = get(chain, position)?;
+            let record = data::get(read, position)?.ok_or(Tampered::new(position))?;
+            let expected = amendments.get(&position.value()).unwrap_or(&digest);
+
+            if *expected != record_digest(read, &record)? {
+                return Err(Tampered::new(position).into());
+            }
+
+            if record.archived.is_some() {
+                archived.push(position);
+            } else if record.redacted {
+                redacted.push(position);
+            }
+
+            hash = link(&hash, position, &digest);
+
+            if hash != stored {
+                return Err(Tampered::new(position).into());
+            }
+        }
+
+        Ok(Verification::new(hash, redacted, archived))
+    }
+
+    // Insertion
+
+    pub fn insert(
+        write: &mut Write<'_>,
+        position: Position,
+        event: &HashedEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        let chain = chain(write.partitions)?;
+
+        let previous = match write.chain {
+            Some(hash) => hash,
+            None => previous(chain, position)?,
+        };
+
+        let digest = event_digest(event, false, None);
+        let hash = link(&previous, position, &digest);
+
+        write
+            .batch
+            .insert(chain, position.value().to_be_bytes(), value(&digest, &hash));
+        write.chain = Some(hash);
+
+        Ok(())
+    }
+
+    // Amendment
+
+    pub fn redact(
+        write: &mut Write<'_>,
+        position: Position,
+        replacement: &HashedEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        amend(write, position, event_digest(replacement, true, None))
+    }
+
+    pub fn tombstone(
+        write: &mut Write<'_>,
+        position: Position,
+        event: &HashedEvent,
+        archived: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let identifier = event.descriptor.identifer();
+        let version = event.descriptor.version().value();
+        let digest = digest::<&str>(identifier.value(), version, &[], &[], false, Some(archived));
+
+        amend(write, position, digest)
+    }
+
+    fn amend(
+        write: &mut Write<'_>,
+        position: Position,
+        current: [u8; 32],
+    ) -> Result<(), Box<dyn Error>> {
+        if write.partitions.chain.is_none() {
+            return Ok(());
+        }
+
+        let read = Read::new(write.partitions);
+        let record = data::get(&read, position)?.ok_or("amended event missing")?;
+        let previous = record_digest(&read, &record)?;
+
+        amendment::insert(write, position, previous, current)
+    }
+
+    fn amendments(
+        read: &Read<'_>,
+        chain: &PartitionHandle,
+        end: Position,
+    ) -> Result<BTreeMap<u64, [u8; 32]>, Box<dyn Error>> {
+        let mut expected = BTreeMap::new();
+
+        for amendment in amendment::all(read)? {
+            let position = amendment.position;
+
+            if position.value() >= end.value() {
+                continue;
+            }
+
+            let current = match expected.get(&position.value()) {
+                Some(current) => *current,
+                None => get(chain, position)?.0,
+            };
+
+            if current != amendment.previous {
+                return Err(Tampered::new(position).into());
+            }
+
+            expected.insert(position.value(), amendment.current);
+        }
+
+        Ok(expected)
+    }
+
+    // Maintenance
+
+    pub fn backfill(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+        let Some(chain) = &partitions.chain else {
+            return Ok(());
+        };
+
+        let read = Read::new(partitions);
+        let len = data::len(&read)?;
+        let start = match chain.last_key_value()? {
+            Some((key, _)) => key.as_ref().get_u64() + 1,
+            None => 0,
+        };
+
+        if metadata::is_chained(&read)? {
+            if start != len {
+                return Err(Tampered::new(Position::from(start.min(len))).into());
+            }
+
+            return Ok(());
+        }
+
+        let resumable = match metadata::chaining(&read)? {
+            Some(progress) => progress + 1 == start,
+            None => start == 0 && amendment::head(&read)?.0 == 0,
+        };
+
+        if !resumable {
+            return Err(Tampered::new(Position::from(start)).into());
+        }
+
+        let mut batch = database.batch();
+        let mut hash = previous(chain, Position::from(start))?;
+
+        for position in data::iterate(&read, Some(Position::from(start))) {
+            let position = Position::from(position);
+            let record = data::get(&read, position)?.ok_or("event missing during backfill")?;
+
+            let digest = record_digest(&read, &record)?;
+
+            hash = link(&hash, position, &digest);
+
+            batch.insert(chain, position.value().to_be_bytes(), value(&digest, &hash));
+
+            if batch.len() >= BACKFILL_BATCH_LEN {
+                metadata::insert_chaining(
+                    &mut Write::new(&mut batch, partitions),
+                    position.value(),
+                )?;
+
+                batch.commit()?;
+                batch = database.batch();
+            }
+        }
+
+        let mut write = Write::new(&mut batch, partitions);
+
+        metadata::remove_chaining(&mut write)?;
+        metadata::insert_chained(&mut write)?;
+
+        batch.commit()?;
+
+        Ok(())
+    }
+
+    // Hashes
+
+    fn digest<T>(
+        identifier: &str,
+        version: u8,
+        tags: &[T],
+        data: &[u8],
+        redacted: bool,
+        archived: Option<u64>,
+    ) -> [u8; 32]
+    where
+        T: AsRef<str>,
+    {
+        let mut hasher = Sha256::new();
+
+        hasher.update((identifier.len() as u64).to_be_bytes());
+        hasher.update(identifier);
+        hasher.update([version]);
+        hasher.update((tags.len() as u64).to_be_bytes());
+
+        for tag in tags {
+            hasher.update((tag.as_ref().len() as u64).to_be_bytes());
+            hasher.update(tag.as_ref());
+        }
+
+        hasher.update((data.len() as u64).to_be_bytes());
+        hasher.update(data);
+        hasher.update([u8::from(redacted)]);
+
+        if let Some(archived) = archived {
+            hasher.update(archived.to_be_bytes());
+        }
+
+        hasher.finalize().into()
+    }
+
+    fn event_digest(event: &HashedEvent, redacted: bool, archived: Option<u64>) -> [u8; 32] {
+        let tags = event
+            .tags
+            .iter()
+            .map(|tag| tag.encode())
+            .collect::<Vec<_>>();
+
+        digest(
+            event.descriptor.identifer().value(),
+            event.descriptor.version().value(),
+            &tags,
+            &event.data,
+            redacted,
+            archived,
+        )
+    }
+
+    fn record_digest(read: &Read<'_>, record: &Record) -> Result<[u8; 32], Box<dyn Error>> {
+        let identifier = reference::identifier(read, record.identifier)?;
+        let tags = record
+            .tags
+            .iter()
+            .map(|tag| Ok(reference::tag(read, *tag)?.encode().into_owned()))
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        Ok(digest(
+            identifier.value(),
+            record.version.value(),
+            &tags,
+            &record.data,
+            record.redacted,
+            record.archived,
+        ))
+    }
+
+    fn link(previous: &[u8; 32], position: Position, digest: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+
+        hasher.update(previous);
+        hasher.update(position.value().to_be_bytes());
+        hasher.update(digest);
+        hasher.finalize().into()
+    }
+
+    // Entries
+
+    fn chain(partitions: &Partitions) -> Result<&PartitionHandle, Box<dyn Error>> {
+        partitions
+            .chain
+            .as_ref()
+            .ok_or_else(|| "chain partition unavailable".into())
+    }
+
+    fn get(
+        chain: &PartitionHandle,
+        position: Position,
+    ) -> Result<([u8; 32], [u8; 32]), Box<dyn Error>> {
+        let value = chain
+            .get(position.value().to_be_bytes())?
+            .ok_or(Tampered::new(position))?;
+
+        read_value(&value).ok_or_else(|| Tampered::new(position).into())
+    }
+
+    fn previous(chain: &PartitionHandle, position: Position) -> Result<[u8; 32], Box<dyn Error>> {
+        match position.value().checked_sub(1) {
+            Some(previous) => get(chain, Position::from(previous)).map(|(_, hash)| hash),
+            None => Ok(GENESIS),
+        }
+    }
+
+    fn read_value(value: &[u8]) -> Option<([u8; 32], [u8; 32])> {
+        let digest = value.get(..HASH_LEN)?.try_into().ok()?;
+        let hash = value.get(HASH_LEN..)?.try_into().ok()?;
+
+        Some((digest, hash))
+    }
+
+    fn value(digest: &[u8; 32], hash: &[u8; 32]) -> Vec<u8> {
+        let mut value = Vec::with_capacity(HASH_LEN * 2);
+
+        value.put_slice(digest);
+        value.put_slice(hash);
+
+        value
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        error::Error,
+        ops::Bound,
+    };
+
+    use crate::{
+        model::{
+            Event,
+            Position,
+            Stream,
+            Tag,
+        },
+        options::{
+            Durability,
+            PartitionOptions,
+            StreamOptions,
+        },
+        persistence::{
+            Tampered,
+            backend::{
+                Backend as _,
+                MemoryBackend,
+                Operation,
+                PartitionHandle,
+            },
+            chain,
+            data,
+            metadata,
+        },
+    };
+
+    fn populate(backend: &MemoryBackend) -> Result<(), Box<dyn Error>> {
+        let mut stream = Stream::with_backend(backend.clone(), StreamOptions::default())?;
+
+        stream.append((0..3).map(|index: u8| {
+            Event::new(vec![index], ("order-placed", 0), [Tag::from("order:1")])
+        }))?;
+
+        Ok(())
+    }
+
+    fn partition(backend: &MemoryBackend, name: &str) -> Result<PartitionHandle, Box<dyn Error>> {
+        let partition = backend.open_partition(name, &PartitionOptions::default())?;
+
+        Ok(PartitionHandle::new(partition))
+    }
+
+    fn tampered_at(err: &(dyn Error + 'static)) -> Option<u64> {
+        err.downcast_ref::<Tampered>()
+            .map(|tampered| tampered.position().value())
+    }
+
+    #[test]
+    fn verification_detects_replaced_events() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+
+        populate(&backend)?;
+
+        let data = partition(&backend, data::PARTITION_NAME)?;
+        let replacement = data.get(0u64.to_be_bytes())?.ok_or("missing event")?;
+
+        backend.commit(
+            vec![Operation::Insert {
+                partition: data::PARTITION_NAME.into(),
+                key: 1u64.to_be_bytes()[..].into(),
+                value: replacement,
+            }],
+            Durability::Sync,
+        )?;
+
+        let stream = Stream::with_backend(backend, StreamOptions::default())?;
+
+        assert!(stream.verify_chain(..Position::from(1u64)).is_ok());
+
+        let err = stream
+            .verify_chain(..)
+            .err()
+            .ok_or("tampering undetected")?;
+
+        assert_eq!(tampered_at(&*err), Some(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reopening_rejects_truncated_chains() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+
+        populate(&backend)?;
+
+        backend.commit(
+            vec![Operation::Remove {
+                partition: chain::PARTITION_NAME.into(),
+                key: 2u64.to_be_bytes()[..].into(),
+            }],
+            Durability::Sync,
+        )?;
+
+        let err = Stream::with_backend(backend, StreamOptions::default())
+            .err()
+            .ok_or("truncation undetected")?;
+
+        assert_eq!(tampered_at(&*err), Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn reopening_rejects_unmarked_chains() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+
+        populate(&backend)?;
+
+        backend.commit(
+            vec![Operation::Remove {
+                partition: metadata::PARTITION_NAME.into(),
+                key: b"chained"[..].into(),
+            }],
+            Durability::Sync,
+        )?;
+
+        let err = Stream::with_backend(backend.clone(), StreamOptions::default())
+            .err()
+            .ok_or("missing marker undetected")?;
+
+        assert_eq!(tampered_at(&*err), Some(3));
+        assert_eq!(
+            partition(&backend, chain::PARTITION_NAME)?.iter().count(),
+            3
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reopening_backfills_new_chains() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+
+        populate(&backend)?;
+
+        let verified =
+            Stream::with_backend(backend.clone(), StreamOptions::default())?.verify_chain(..)?;
+
+        backend.delete_partition(chain::PARTITION_NAME)?;
+        backend.commit(
+            vec![Operation::Remove {
+                partition: metadata::PARTITION_NAME.into(),
+                key: b"chained"[..].into(),
+            }],
+            Durability::Sync,
+        )?;
+
+        let stream = Stream::with_backend(backend, StreamOptions::default())?;
+
+        assert_eq!(stream.verify_chain(..)?.hash(), verified.hash());
+
+        Ok(())
+    }
+
+    #[test]
+    fn verification_rejects_overflowing_ranges() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+
+        populate(&backend)?;
+
+        let stream = Stream::with_backend(backend, StreamOptions::default())?;
+        let range = (
+            Bound::Excluded(Position::from(u64::MAX)),
+            Bound::Included(Position::from(u64::MAX)),
+        );
+
+        assert!(stream.verify_chain(..=Position::from(u64::MAX)).is_err());
+        assert!(stream.verify_chain(range).is_err());
+
+        Ok(())
+    }
+}
@@ -1,18 +1,25 @@
 use std::error::Error;
 
 use bytes::Buf as _;
-use fjall::{
-    PartitionCreateOptions,
-    PartitionHandle,
-};
 
 use crate::{
     model::Position,
     persistence::{
+        self,
         Database,
         HashedEvent,
+        Partitions,
         Read,
+        Record,
         Write,
+        backend::{
+            KeyValues,
+            PartitionHandle,
+        },
+        blob::{
+            self,
+            Payload,
+        },
     },
 };
 
@@ -20,14 +27,17 @@ use crate::{
 // Data
 // =================================================================================================
 
-static PARTITION_NAME: &str = "data";
+pub(crate) static PARTITION_NAME: &str = "data";
+pub static FORMAT_VERSION: u8 = 1;
+pub static MAX_TAGS_LEN: usize = 253;
 
 // Partition
 
-pub fn partition(database: &Database) -> Result<PartitionHandle, Box<dyn Error>> {
-    Ok(database
-        .as_ref()
-        .open_partition(PARTITION_NAME, PartitionCreateOptions::default())?)
+pub fn partition(
+    database: &Database,
+    stream: Option<&str>,
+) -> Result<PartitionHandle, Box<dyn Error>> {
+    persistence::partition(database, stream, PARTITION_NAME, &database.options().data)
 }
 
 // Properties
@@ -49,10 +59,92 @@ pub fn len(read: &Read<'_>) -> Result<u64, Box<dyn Error>> {
     }
 }
 
+// Retrieval
+
+pub fn get(read: &Read<'_>, position: Position) -> Result<Option<Record>, Box<dyn Error>> {
+    let Some(mut record) = event::get(read, position)? else {
+        return Ok(None);
+    };
+
+    if let Some(len) = record.offloaded {
+        record.data = blob::get(read, position, len)?;
+    }
+
+    Ok(Some(record))
+}
+
+pub fn payload(read: &Read<'_>, position: Position) -> Result<Option<Payload>, Box<dyn Error>> {
+    let Some(record) = event::get(read, position)? else {
+        return Ok(None);
+    };
+
+    match record.offloaded {
+        Some(len) => blob::payload(read, position, len).map(Some),
+        None => Ok(Some(Payload::inline(position, record.data))),
+    }
+}
+
+// Iteration
+
+pub fn iterate(read: &Read<'_>, position: Option<Position>) -> impl Iterator<Item = u64> + use<> {
+    let lower = position.map_or(0, Position::value).to_be_bytes();
+
+    read.partitions.data.range(lower..).map(|key_value| {
+        let (key, _) = key_value.expect("invalid key/value during iteration");
+
+        key.as_ref().get_u64()
+    })
+}
+
+// Backup
+
+pub fn backup(partition: &PartitionHandle, position: Option<Position>) -> KeyValues {
+    match position {
+        Some(position) => partition.range(..position.value().to_be_bytes()),
+        None => partition.iter(),
+    }
+}
+
+// Maintenance
+
+pub fn backfill_checksums(
+    database: &Database,
+    partitions: &Partitions,
+) -> Result<(), Box<dyn Error>> {
+    event::backfill(database, partitions)
+}
+
+pub fn migrate(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+    event::migrate(database, partitions)
+}
+
 // Insertion
 
-pub fn insert(write: &mut Write<'_>, position: Position, event: &HashedEvent) {
-    event::insert(write, position, event);
+pub fn insert(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+) -> Result<(), Box<dyn Error>> {
+    event::insert(write, position, event)
+}
+
+pub fn redact(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+) -> Result<(), Box<dyn Error>> {
+    event::redact(write, position, event)
+}
+
+// Removal
+
+pub fn remove(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+    archived: u64,
+) -> Result<(), Box<dyn Error>> {
+    event::tombstone(write, position, event, archived)
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -60,47 +152,428 @@ pub fn insert(write: &mut Write<'_>, position: Position, event: &HashedEvent) {
 // Event
 
 mod event {
-    use bytes::BufMut as _;
+    use std::{
+        error::Error,
+        ops::Bound,
+    };
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+    use ed25519_dalek::Signature;
+    use xxhash_rust::xxh3;
 
     use crate::{
         model::Position,
+        options::Encoding,
         persistence::{
+            Corrupted,
+            Database,
             HashedEvent,
+            Partitions,
+            Read,
+            Record,
             Write,
+            backend::Batch,
+            blob,
+            data::MAX_TAGS_LEN,
+            metadata,
         },
     };
 
+    static BACKFILL_BATCH_LEN: usize = 1024;
+    static CHECKSUM_LEN: usize = size_of::<u64>();
+    static EXTENDED: u8 = u8::MAX - 1;
+    static OFFLOADED: u8 = 0b0000_0100;
+    static REDACTED: u8 = 0b0000_0001;
+    static SIGNED: u8 = 0b0000_0010;
+    static TOMBSTONE: u8 = u8::MAX;
+
+    // Retrieval
+
+    pub fn get(read: &Read<'_>, position: Position) -> Result<Option<Record>, Box<dyn Error>> {
+        let key = position.value().to_be_bytes();
+
+        let Some(value) = read.partitions.data.get(key)? else {
+            return Ok(None);
+        };
+
+        let value = if read.partitions.checksums {
+            verify(&value).ok_or_else(|| Corrupted::new(position))?
+        } else {
+            &value
+        };
+
+        read_value(value, read.partitions.encoding, read.partitions.legacy).map(Some)
+    }
+
+    // Maintenance
+
+    pub fn backfill(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+        let mut batch = database.batch();
+
+        for key_value in &partitions.data {
+            let (key, value) = key_value?;
+
+            if verify(&value).is_some() {
+                continue;
+            }
+
+            let mut value = value.to_vec();
+
+            seal(&mut value);
+
+            batch.insert(&partitions.data, key, value);
+
+            if batch.len() >= BACKFILL_BATCH_LEN {
+                batch.commit()?;
+                batch = database.batch();
+            }
+        }
+
+        batch.commit()?;
+
+        Ok(())
+    }
+
+    pub fn migrate(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+        let read = Read::new(partitions);
+        let lower = metadata::migrated(&read)?.map_or(Bound::Unbounded, |migrated| {
+            Bound::Excluded(migrated.to_be_bytes())
+        });
+
+        let mut batch = database.batch();
+        let mut last = None;
+
+        for key_value in partitions.data.range((lower, Bound::Unbounded)) {
+            let (key, value) = key_value?;
+
+            last = Some(key.as_ref().get_u64());
+
+            let mut header = &value[..];
+
+            partitions.encoding.get_id(&mut header);
+            header.advance(1);
+
+            let tags_len = header.get_u8();
+
+            if tags_len >= EXTENDED {
+                let offset = value.len() - header.len() - 1;
+                let mut migrated = Vec::with_capacity(value.len() + 2);
+
+                migrated.put_slice(&value[..offset]);
+                migrated.put_u8(EXTENDED);
+                migrated.put_u8(0);
+                migrated.put_u8(tags_len);
+                migrated.put_slice(header);
+
+                batch.insert(&partitions.data, key, migrated);
+            }
+
+            if batch.len() >= BACKFILL_BATCH_LEN {
+                progress(&mut batch, partitions, last)?;
+
+                batch.commit()?;
+                batch = database.batch();
+            }
+        }
+
+        progress(&mut batch, partitions, last)?;
+
+        batch.commit()?;
+
+        Ok(())
+    }
+
+    fn progress(
+        batch: &mut Batch,
+        partitions: &Partitions,
+        last: Option<u64>,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(last) = last else {
+            return Ok(());
+        };
+
+        let mut write = Write::new(batch, partitions);
+
+        metadata::insert_migrated(&mut write, last)
+    }
+
     // Insertion
 
-    pub fn insert(write: &mut Write<'_>, position: Position, event: &HashedEvent) {
+    pub fn insert(
+        write: &mut Write<'_>,
+        position: Position,
+        event: &HashedEvent,
+    ) -> Result<(), Box<dyn Error>> {
         let key = position.value().to_be_bytes();
+        let flags = offload(write, position, event)?;
 
         let mut value = Vec::new();
 
-        write_value(&mut value, event);
+        write_value(
+            &mut value,
+            write.partitions.encoding,
+            event,
+            event.signature.as_ref(),
+            flags,
+        )?;
+        seal(&mut value);
 
         write.batch.insert(&write.partitions.data, key, value);
+
+        Ok(())
+    }
+
+    pub fn redact(
+        write: &mut Write<'_>,
+        position: Position,
+        event: &HashedEvent,
+    ) -> Result<(), Box<dyn Error>> {
+        let key = position.value().to_be_bytes();
+        let record = get(&Read::new(write.partitions), position)?;
+        let flags = offload(write, position, event)?;
+
+        if record
+            .as_ref()
+            .is_some_and(|record| record.offloaded.is_some())
+        {
+            let retained = if flags & OFFLOADED == 0 {
+                0
+            } else {
+                event.data.len() as u64
+            };
+
+            blob::remove(write, position, retained)?;
+        }
+
+        let signature = record.and_then(|record| record.signature);
+
+        let mut value = Vec::new();
+
+        write_value(
+            &mut value,
+            write.partitions.encoding,
+            event,
+            signature.as_ref(),
+            flags | REDACTED,
+        )?;
+        seal(&mut value);
+
+        write.batch.insert(&write.partitions.data, key, value);
+
+        Ok(())
+    }
+
+    pub fn tombstone(
+        write: &mut Write<'_>,
+        position: Position,
+        event: &HashedEvent,
+        archived: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let key = position.value().to_be_bytes();
+        let record = get(&Read::new(write.partitions), position)?;
+
+        if record.is_some_and(|record| record.offloaded.is_some()) {
+            blob::remove(write, position, 0)?;
+        }
+
+        let mut value = Vec::new();
+
+        write_tombstone(&mut value, write.partitions.encoding, event, archived);
+        seal(&mut value);
+
+        write.batch.insert(&write.partitions.data, key, value);
+
+        Ok(())
+    }
+
+    // Offloading
+
+    fn offload(
+        write: &mut Write<'_>,
+        position: Position,
+        event: &HashedEvent,
+    ) -> Result<u8, Box<dyn Error>> {
+        let offloaded = write
+            .partitions
+            .blob_threshold
+            .is_some_and(|threshold| event.data.len() as u64 > threshold);
+
+        if offloaded {
+            blob::insert(write, position, &event.data)?;
+
+            Ok(OFFLOADED)
+        } else {
+            Ok(0)
+        }
     }
 
     // Values
 
-    fn write_value(value: &mut Vec<u8>, event: &HashedEvent) {
+    fn read_value(
+        mut value: &[u8],
+        encoding: Encoding,
+        legacy: bool,
+    ) -> Result<Record, Box<dyn Error>> {
+        let descriptor_identifier = encoding.get_id(&mut value);
+        let descriptor_version = value.get_u8();
+        let mut tags_len = value.get_u8();
+        let mut flags = 0;
+
+        if legacy {
+            let tags = (0..tags_len).map(|_| encoding.get_id(&mut value)).collect();
+
+            return Ok(Record::new(
+                value.to_vec(),
+                descriptor_identifier,
+                descriptor_version.into(),
+                tags,
+                None,
+                false,
+                None,
+                None,
+            ));
+        }
+
+        if tags_len == TOMBSTONE {
+            let archived = value.get_u64();
+
+            return Ok(Record::new(
+                Vec::new(),
+                descriptor_identifier,
+                descriptor_version.into(),
+                Vec::new(),
+                Some(archived),
+                false,
+                None,
+                None,
+            ));
+        }
+
+        if tags_len == EXTENDED {
+            flags = value.get_u8();
+            tags_len = value.get_u8();
+        }
+
+        let tags = (0..tags_len).map(|_| encoding.get_id(&mut value)).collect();
+
+        let signature = if flags & SIGNED == 0 {
+            None
+        } else {
+            let key_id_len = usize::from(value.get_u8());
+            let key_id = String::from_utf8(value[..key_id_len].to_vec())?;
+
+            value.advance(key_id_len);
+
+            let signature = Signature::from_slice(&value[..Signature::BYTE_SIZE])?;
+
+            value.advance(Signature::BYTE_SIZE);
+
+            Some((key_id, signature))
+        };
+
+        let (data, offloaded) = if flags & OFFLOADED == 0 {
+            (value.to_vec(), None)
+        } else {
+            (Vec::new(), Some(value.get_u64()))
+        };
+
+        Ok(Record::new(
+            data,
+            descriptor_identifier,
+            descriptor_version.into(),
+            tags,
+            None,
+            flags & REDACTED != 0,
+            signature,
+            offloaded,
+        ))
+    }
+
+    fn write_tombstone(
+        value: &mut Vec<u8>,
+        encoding: Encoding,
+        event: &HashedEvent,
+        archived: u64,
+    ) {
         let descriptor_identifier = event.descriptor.identifer().hash();
         let descriptor_version = event.descriptor.version().value();
-        let tags_len = u8::try_from(event.tags.len()).expect("max tag count exceeded");
 
-        value.put_u64(descriptor_identifier);
+        encoding.put_id(value, descriptor_identifier);
         value.put_u8(descriptor_version);
+        value.put_u8(TOMBSTONE);
+        value.put_u64(archived);
+    }
+
+    fn write_value(
+        value: &mut Vec<u8>,
+        encoding: Encoding,
+        event: &HashedEvent,
+        signature: Option<&(String, Signature)>,
+        flags: u8,
+    ) -> Result<(), Box<dyn Error>> {
+        let descriptor_identifier = event.descriptor.identifer().hash();
+        let descriptor_version = event.descriptor.version().value();
+        let tags_len = u8::try_from(event.tags.len())
+            .ok()
+            .filter(|tags_len| usize::from(*tags_len) <= MAX_TAGS_LEN)
+            .ok_or_else(|| format!("events are limited to {MAX_TAGS_LEN} tags"))?;
+        let flags = if signature.is_some() {
+            flags | SIGNED
+        } else {
+            flags
+        };
+
+        encoding.put_id(value, descriptor_identifier);
+        value.put_u8(descriptor_version);
+
+        if flags != 0 {
+            value.put_u8(EXTENDED);
+            value.put_u8(flags);
+        }
+
         value.put_u8(tags_len);
 
         for tag in &event.tags {
             let tag = tag.hash();
 
-            value.put_u64(tag);
+            encoding.put_id(value, tag);
+        }
+
+        if let Some((key_id, signature)) = signature {
+            let key_id_len = u8::try_from(key_id.len())
+                .map_err(|_| format!("signing key id exceeds {} bytes", u8::MAX))?;
+
+            value.put_u8(key_id_len);
+            value.put_slice(key_id.as_bytes());
+            value.put_slice(&signature.to_bytes());
         }
 
         let data = &event.data;
 
-        value.put_slice(data);
+        if flags & OFFLOADED == 0 {
+            value.put_slice(data);
+        } else {
+            value.put_u64(data.len() as u64);
+        }
+
+        Ok(())
+    }
+
+    // Checksums
+
+    fn seal(value: &mut Vec<u8>) {
+        let checksum = xxh3::xxh3_64(value);
+
+        value.put_u64(checksum);
+    }
+
+    fn verify(value: &[u8]) -> Option<&[u8]> {
+        let len = value.len().checked_sub(CHECKSUM_LEN)?;
+        let (value, mut checksum) = value.split_at(len);
+
+        (checksum.get_u64() == xxh3::xxh3_64(value)).then_some(value)
     }
 }
@@ -1,16 +1,34 @@
-use std::error::Error;
-
-use fjall::{
-    PartitionCreateOptions,
-    PartitionHandle,
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    iter,
+    sync::{
+        Arc,
+        OnceLock,
+    },
 };
 
+use bytes::Buf as _;
+
 use crate::{
     model::Position,
+    options::Extractor,
     persistence::{
+        self,
         Database,
         HashedEvent,
+        HashedQuery,
+        HashedSelector,
+        Hasher,
+        POSITION_LEN,
+        Partitions,
+        Read,
         Write,
+        backend::{
+            KvPair,
+            PartitionHandle,
+        },
+        metadata,
     },
 };
 
@@ -19,14 +37,33 @@ use crate::{
 // =================================================================================================
 
 static ID_LEN: usize = size_of::<u8>();
-static PARTITION_NAME: &str = "index";
+pub(crate) static PARTITION_NAME: &str = "index";
 
 // Partitions
 
-pub fn partition(database: &Database) -> Result<PartitionHandle, Box<dyn Error>> {
-    Ok(database
-        .as_ref()
-        .open_partition(PARTITION_NAME, PartitionCreateOptions::default())?)
+pub fn partition(
+    database: &Database,
+    stream: Option<&str>,
+) -> Result<PartitionHandle, Box<dyn Error>> {
+    persistence::partition(database, stream, PARTITION_NAME, &database.options().index)
+}
+
+// Backup
+
+pub fn backup(
+    partition: &PartitionHandle,
+    position: Option<Position>,
+) -> impl Iterator<Item = Result<KvPair, Box<dyn Error>>> + use<> {
+    partition
+        .iter()
+        .filter(move |key_value| match (key_value, position) {
+            (Ok((key, _)), Some(position)) => {
+                let mut key = &key[key.len() - POSITION_LEN..];
+
+                key.get_u64() < position.value()
+            }
+            _ => true,
+        })
 }
 
 // Partition Insertion
@@ -34,6 +71,246 @@ pub fn partition(database: &Database) -> Result<PartitionHandle, Box<dyn Error>>
 pub fn insert(write: &mut Write<'_>, position: Position, event: &HashedEvent) {
     descriptor::insert(write, position, &event.descriptor);
     tags::insert(write, position, &event.tags);
+    extracted::insert(write, position, &event.indexes);
+
+    if write.partitions.timestamps {
+        time::insert(write, position, persistence::timestamp());
+    }
+}
+
+// Partition Removal
+
+pub fn remove(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+) -> Result<(), Box<dyn Error>> {
+    descriptor::remove(write, position, &event.descriptor);
+    tags::remove(write, position, &event.tags);
+    extracted::remove(write, position, &event.indexes);
+    time::remove(write, position)
+}
+
+// Partition Redaction
+
+pub fn redact(
+    write: &mut Write<'_>,
+    position: Position,
+    event: &HashedEvent,
+    replacement: &HashedEvent,
+) {
+    let removed = event
+        .indexes
+        .iter()
+        .filter(|index| !replacement.indexes.contains(index))
+        .cloned()
+        .collect::<Vec<_>>();
+    let inserted = replacement
+        .indexes
+        .iter()
+        .filter(|index| !event.indexes.contains(index))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    extracted::remove(write, position, &removed);
+    extracted::insert(write, position, &inserted);
+}
+
+// Partition Maintenance
+
+pub fn backfill_extracted(
+    database: &Database,
+    partitions: &Partitions,
+    name: u64,
+    extractor: &Extractor,
+) -> Result<(), Box<dyn Error>> {
+    extracted::backfill(database, partitions, name, extractor)
+}
+
+pub fn clear_extracted(
+    database: &Database,
+    partitions: &Partitions,
+    name: u64,
+) -> Result<(), Box<dyn Error>> {
+    extracted::clear(database, partitions, name)
+}
+
+pub fn backfill_keys(
+    database: &Database,
+    partitions: &Partitions,
+    hasher: Hasher,
+) -> Result<(), Box<dyn Error>> {
+    tags::backfill_keys(database, partitions, hasher)
+}
+
+pub fn backfill_prefixes(
+    database: &Database,
+    partitions: &Partitions,
+) -> Result<(), Box<dyn Error>> {
+    tags::backfill_prefixes(database, partitions)
+}
+
+pub fn backfill_timestamps(
+    database: &Database,
+    partitions: &Partitions,
+) -> Result<(), Box<dyn Error>> {
+    time::backfill(database, partitions)
+}
+
+// Partition Iteration
+
+pub fn aged(read: &Read<'_>, timestamp: u64) -> impl Iterator<Item = u64> + use<> {
+    time::iterate(read, timestamp)
+}
+
+pub fn iterate(
+    read: &Read<'_>,
+    position: Option<Position>,
+    query: &HashedQuery,
+) -> impl Iterator<Item = u64> + use<> {
+    let partitions = Arc::new(read.partitions.clone());
+
+    let selectors = query
+        .selectors()
+        .iter()
+        .map(|selector| iterate_selector(read, &partitions, position, selector))
+        .collect();
+
+    merge::union(selectors)
+}
+
+fn iterate_selector(
+    read: &Read<'_>,
+    partitions: &Arc<Partitions>,
+    position: Option<Position>,
+    selector: &HashedSelector,
+) -> Box<dyn Iterator<Item = u64>> {
+    let mut iterators = Vec::<(Option<u64>, merge::Seek)>::new();
+
+    if !selector.specifiers().is_empty() {
+        let estimate = selector
+            .specifiers()
+            .iter()
+            .map(|specifier| {
+                estimate(metadata::identifier_count(
+                    read,
+                    specifier.identifer().hash(),
+                ))
+            })
+            .sum::<Option<u64>>();
+
+        let specifiers = selector.specifiers().to_vec();
+
+        let seek = seek(partitions, move |read, position| {
+            let specifiers = specifiers
+                .iter()
+                .map(|specifier| descriptor::iterate(read, position, specifier))
+                .collect();
+
+            Box::new(merge::union(specifiers))
+        });
+
+        iterators.push((estimate, seek));
+    }
+
+    for tag in selector.tags() {
+        let estimate = estimate(metadata::tag_count(read, tag.hash()));
+        let tag = tag.clone();
+
+        let seek = seek(partitions, move |read, position| {
+            tags::iterate(read, position, &tag)
+        });
+
+        iterators.push((estimate, seek));
+    }
+
+    for prefix in selector.tag_prefixes() {
+        let prefix = prefix.clone();
+
+        let seek = materialise(partitions, move |read| {
+            tags::prefix_positions(read, &prefix)
+        });
+
+        iterators.push((None, seek));
+    }
+
+    for tag_key in selector.tag_keys() {
+        if tag_key.values().is_empty() {
+            let key = tag_key.hash();
+
+            let seek = seek(partitions, move |read, position| {
+                tags::iterate_key(read, position, key)
+            });
+
+            iterators.push((None, seek));
+        } else {
+            let estimate = tag_key
+                .values()
+                .iter()
+                .map(|tag| estimate(metadata::tag_count(read, tag.hash())))
+                .sum::<Option<u64>>();
+
+            let values = tag_key.values().to_vec();
+
+            let seek = seek(partitions, move |read, position| {
+                let values = values
+                    .iter()
+                    .map(|tag| tags::iterate(read, position, tag))
+                    .collect();
+
+                Box::new(merge::union(values))
+            });
+
+            iterators.push((estimate, seek));
+        }
+    }
+
+    for range in selector.index_ranges() {
+        let range = range.clone();
+
+        let seek = materialise(partitions, move |read| extracted::positions(read, &range));
+
+        iterators.push((None, seek));
+    }
+
+    if iterators.iter().any(|(estimate, _)| *estimate == Some(0)) {
+        return Box::new(iter::empty());
+    }
+
+    iterators.sort_by_key(|(estimate, _)| estimate.unwrap_or(u64::MAX));
+
+    let iterators = iterators.into_iter().map(|(_, seek)| seek).collect();
+
+    Box::new(merge::intersection(position, iterators))
+}
+
+fn estimate(count: Result<Option<u64>, Box<dyn Error>>) -> Option<u64> {
+    count.ok().flatten()
+}
+
+fn seek<F>(partitions: &Arc<Partitions>, open: F) -> merge::Seek
+where
+    F: Fn(&Read<'_>, Option<Position>) -> Box<dyn Iterator<Item = u64>> + 'static,
+{
+    let partitions = partitions.clone();
+
+    Box::new(move |position| open(&Read::new(&partitions), position))
+}
+
+fn materialise<F>(partitions: &Arc<Partitions>, collect: F) -> merge::Seek
+where
+    F: Fn(&Read<'_>) -> BTreeSet<u64> + 'static,
+{
+    let partitions = partitions.clone();
+    let positions = OnceLock::new();
+
+    Box::new(move |position| {
+        let positions = positions
+            .get_or_init(|| Arc::new(collect(&Read::new(&partitions))))
+            .clone();
+
+        Box::new(merge::within(positions, position))
+    })
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -45,6 +322,8 @@ mod descriptor {
         model::Position,
         persistence::{
             HashedDescriptor,
+            HashedSpecifier,
+            Read,
             Write,
         },
     };
@@ -57,6 +336,22 @@ mod descriptor {
         forward::insert(write, position, descriptor);
     }
 
+    // Removal
+
+    pub fn remove(write: &mut Write<'_>, position: Position, descriptor: &HashedDescriptor) {
+        forward::remove(write, position, descriptor);
+    }
+
+    // Iteration
+
+    pub fn iterate(
+        read: &Read<'_>,
+        position: Option<Position>,
+        specifier: &HashedSpecifier,
+    ) -> Box<dyn Iterator<Item = u64>> {
+        Box::new(forward::iterate(read, position, specifier))
+    }
+
     // Forward Index
 
     mod forward {
@@ -64,13 +359,10 @@ mod descriptor {
             Buf as _,
             BufMut as _,
         };
-        use fjall::{
-            Error,
-            Slice,
-        };
 
         use crate::{
             model::Position,
+            options::Encoding,
             persistence::{
                 HashedDescriptor,
                 HashedIdentifier,
@@ -78,6 +370,7 @@ mod descriptor {
                 POSITION_LEN,
                 Read,
                 Write,
+                backend::KeyValues,
                 index::{
                     ID_LEN,
                     descriptor::HASH_LEN,
@@ -87,29 +380,37 @@ mod descriptor {
 
         static INDEX_ID: u8 = 0;
         static KEY_LEN: usize = ID_LEN + HASH_LEN + POSITION_LEN;
-        static PREFIX_LEN: usize = ID_LEN + HASH_LEN;
 
         //  Insertion
 
         pub fn insert(write: &mut Write<'_>, position: Position, descriptor: &HashedDescriptor) {
-            let mut key = [0u8; KEY_LEN];
-
+            let encoding = write.partitions.encoding;
             let identifier = descriptor.identifer();
 
-            write_key(&mut key, position, identifier);
+            let key = write_key(encoding, position, identifier);
 
             let value = descriptor.version().value().to_be_bytes();
 
             write.batch.insert(&write.partitions.index, key, value);
         }
 
+        // Removal
+
+        pub fn remove(write: &mut Write<'_>, position: Position, descriptor: &HashedDescriptor) {
+            let encoding = write.partitions.encoding;
+
+            let key = write_key(encoding, position, descriptor.identifer());
+
+            write.batch.remove(&write.partitions.index, key);
+        }
+
         // Iteration
 
         pub fn iterate(
             read: &Read<'_>,
             position: Option<Position>,
             specifier: &HashedSpecifier,
-        ) -> impl Iterator<Item = u64> {
+        ) -> impl Iterator<Item = u64> + use<> {
             let iterator = match position {
                 Some(position) => iterate_range(read, position, specifier),
                 None => iterate_prefix(read, specifier),
@@ -137,9 +438,7 @@ mod descriptor {
                     }
                 }
 
-                let mut key = &key[..];
-
-                key.advance(ID_LEN + HASH_LEN);
+                let mut key = &key[key.len() - POSITION_LEN..];
 
                 let position = key.get_u64();
 
@@ -147,15 +446,11 @@ mod descriptor {
             })
         }
 
-        fn iterate_prefix(
-            read: &Read<'_>,
-            specifier: &HashedSpecifier,
-        ) -> Box<dyn Iterator<Item = Result<(Slice, Slice), Error>>> {
-            let mut prefix = [0u8; PREFIX_LEN];
-
+        fn iterate_prefix(read: &Read<'_>, specifier: &HashedSpecifier) -> KeyValues {
+            let encoding = read.partitions.encoding;
             let identifier = specifier.identifer();
 
-            write_prefix(&mut prefix, identifier);
+            let prefix = write_prefix(encoding, identifier);
 
             Box::new(read.partitions.index.prefix(prefix))
         }
@@ -164,43 +459,45 @@ mod descriptor {
             read: &Read<'_>,
             position: Position,
             specifier: &HashedSpecifier,
-        ) -> Box<dyn Iterator<Item = Result<(Slice, Slice), Error>>> {
-            let mut lower = [0u8; KEY_LEN];
-            let mut upper = [0u8; KEY_LEN];
-
+        ) -> KeyValues {
+            let encoding = read.partitions.encoding;
             let identifier = specifier.identifer();
 
-            write_key(&mut lower, position, identifier);
+            let lower = write_key(encoding, position, identifier);
 
             let position = Position::from(u64::MAX);
 
-            write_key(&mut upper, position, identifier);
+            let upper = write_key(encoding, position, identifier);
 
             Box::new(read.partitions.index.range(lower..=upper))
         }
 
         // Keys/Prefixes
 
-        fn write_key(key: &mut [u8; KEY_LEN], position: Position, identifier: &HashedIdentifier) {
-            let mut key = &mut key[..];
+        fn write_key(
+            encoding: Encoding,
+            position: Position,
+            identifier: &HashedIdentifier,
+        ) -> Vec<u8> {
+            let mut key = write_prefix(encoding, identifier);
 
-            let index_id = INDEX_ID;
-            let identifier = identifier.hash();
             let position = position.value();
 
-            key.put_u8(index_id);
-            key.put_u64(identifier);
             key.put_u64(position);
+
+            key
         }
 
-        fn write_prefix(prefix: &mut [u8; PREFIX_LEN], identifier: &HashedIdentifier) {
-            let mut prefix = &mut prefix[..];
+        fn write_prefix(encoding: Encoding, identifier: &HashedIdentifier) -> Vec<u8> {
+            let mut prefix = Vec::with_capacity(KEY_LEN);
 
             let index_id = INDEX_ID;
             let identifier = identifier.hash();
 
             prefix.put_u8(index_id);
-            prefix.put_u64(identifier);
+            encoding.put_id(&mut prefix, identifier);
+
+            prefix
         }
     }
 }
@@ -210,10 +507,19 @@ mod descriptor {
 // Tags
 
 mod tags {
+    use std::{
+        collections::BTreeSet,
+        error::Error,
+    };
+
     use crate::{
         model::Position,
         persistence::{
+            Database,
             HashedTag,
+            Hasher,
+            Partitions,
+            Read,
             Write,
         },
     };
@@ -224,19 +530,80 @@ mod tags {
 
     pub fn insert(write: &mut Write<'_>, position: Position, tags: &[HashedTag]) {
         forward::insert(write, position, tags);
+        keys::insert(write, position, tags);
+
+        if write.partitions.indexed.prefixes {
+            ordered::insert(write, position, tags);
+        }
+    }
+
+    // Removal
+
+    pub fn remove(write: &mut Write<'_>, position: Position, tags: &[HashedTag]) {
+        forward::remove(write, position, tags);
+        keys::remove(write, position, tags);
+
+        if write.partitions.indexed.prefixes {
+            ordered::remove(write, position, tags);
+        }
+    }
+
+    // Iteration
+
+    pub fn iterate(
+        read: &Read<'_>,
+        position: Option<Position>,
+        tag: &HashedTag,
+    ) -> Box<dyn Iterator<Item = u64>> {
+        Box::new(forward::iterate(read, position, tag))
+    }
+
+    pub fn iterate_key(
+        read: &Read<'_>,
+        position: Option<Position>,
+        key: u64,
+    ) -> Box<dyn Iterator<Item = u64>> {
+        Box::new(keys::iterate(read, position, key))
+    }
+
+    pub fn prefix_positions(read: &Read<'_>, prefix: &str) -> BTreeSet<u64> {
+        ordered::positions(read, prefix)
+    }
+
+    // Maintenance
+
+    pub fn backfill_keys(
+        database: &Database,
+        partitions: &Partitions,
+        hasher: Hasher,
+    ) -> Result<(), Box<dyn Error>> {
+        keys::backfill(database, partitions, hasher)
+    }
+
+    pub fn backfill_prefixes(
+        database: &Database,
+        partitions: &Partitions,
+    ) -> Result<(), Box<dyn Error>> {
+        ordered::backfill(database, partitions)
     }
 
     // Forward Index
 
     mod forward {
-        use bytes::BufMut as _;
+        use bytes::{
+            Buf as _,
+            BufMut as _,
+        };
 
         use crate::{
             model::Position,
+            options::Encoding,
             persistence::{
                 HashedTag,
                 POSITION_LEN,
+                Read,
                 Write,
+                backend::KeyValues,
                 index::{
                     ID_LEN,
                     tags::HASH_LEN,
@@ -250,27 +617,1059 @@ mod tags {
         // Insertion
 
         pub fn insert(write: &mut Write<'_>, position: Position, tags: &[HashedTag]) {
-            let mut key = [0u8; KEY_LEN];
+            let encoding = write.partitions.encoding;
 
             for tag in tags {
-                write_key(&mut key, position, tag);
+                let key = write_key(encoding, position, tag);
 
                 write.batch.insert(&write.partitions.index, key, []);
             }
         }
 
+        // Removal
+
+        pub fn remove(write: &mut Write<'_>, position: Position, tags: &[HashedTag]) {
+            let encoding = write.partitions.encoding;
+
+            for tag in tags {
+                let key = write_key(encoding, position, tag);
+
+                write.batch.remove(&write.partitions.index, key);
+            }
+        }
+
+        // Iteration
+
+        pub fn iterate(
+            read: &Read<'_>,
+            position: Option<Position>,
+            tag: &HashedTag,
+        ) -> impl Iterator<Item = u64> + use<> {
+            let iterator = match position {
+                Some(position) => iterate_range(read, position, tag),
+                None => iterate_prefix(read, tag),
+            };
+
+            iterator.map(|key_value| {
+                let (key, _) = key_value.expect("invalid key/value during iteration");
+
+                let mut key = &key[key.len() - POSITION_LEN..];
+
+                key.get_u64()
+            })
+        }
+
+        fn iterate_prefix(read: &Read<'_>, tag: &HashedTag) -> KeyValues {
+            let prefix = write_prefix(read.partitions.encoding, tag);
+
+            Box::new(read.partitions.index.prefix(prefix))
+        }
+
+        fn iterate_range(read: &Read<'_>, position: Position, tag: &HashedTag) -> KeyValues {
+            let encoding = read.partitions.encoding;
+
+            let lower = write_key(encoding, position, tag);
+
+            let position = Position::from(u64::MAX);
+
+            let upper = write_key(encoding, position, tag);
+
+            Box::new(read.partitions.index.range(lower..=upper))
+        }
+
         // Keys/Prefixes
 
-        fn write_key(key: &mut [u8; KEY_LEN], position: Position, tag: &HashedTag) {
-            let mut key = &mut key[..];
+        fn write_key(encoding: Encoding, position: Position, tag: &HashedTag) -> Vec<u8> {
+            let mut key = write_prefix(encoding, tag);
 
-            let index_id = INDEX_ID;
-            let tag = tag.hash();
             let position = position.value();
 
-            key.put_u8(index_id);
-            key.put_u64(tag);
             key.put_u64(position);
+
+            key
+        }
+
+        fn write_prefix(encoding: Encoding, tag: &HashedTag) -> Vec<u8> {
+            let mut prefix = Vec::with_capacity(KEY_LEN);
+
+            let index_id = INDEX_ID;
+            let tag = tag.hash();
+
+            prefix.put_u8(index_id);
+            encoding.put_id(&mut prefix, tag);
+
+            prefix
+        }
+    }
+
+    // Key Index
+
+    mod keys {
+        use std::error::Error;
+
+        use bytes::{
+            Buf as _,
+            BufMut as _,
+        };
+
+        use crate::{
+            model::Position,
+            options::Encoding,
+            persistence::{
+                Database,
+                HashedTag,
+                Hasher,
+                POSITION_LEN,
+                Partitions,
+                Read,
+                Write,
+                backend::Slice,
+                data,
+                index::{
+                    ID_LEN,
+                    tags::HASH_LEN,
+                },
+                reference,
+            },
+        };
+
+        static BACKFILL_BATCH_LEN: usize = 1024;
+        static INDEX_ID: u8 = 3;
+        static KEY_LEN: usize = ID_LEN + HASH_LEN + POSITION_LEN;
+
+        // Insertion
+
+        pub fn insert(write: &mut Write<'_>, position: Position, tags: &[HashedTag]) {
+            let encoding = write.partitions.encoding;
+
+            for tag_key in tags.iter().filter_map(HashedTag::key_hash) {
+                let key = write_key(encoding, position, tag_key);
+
+                write.batch.insert(&write.partitions.index, key, []);
+            }
+        }
+
+        // Removal
+
+        pub fn remove(write: &mut Write<'_>, position: Position, tags: &[HashedTag]) {
+            let encoding = write.partitions.encoding;
+
+            for tag_key in tags.iter().filter_map(HashedTag::key_hash) {
+                let key = write_key(encoding, position, tag_key);
+
+                write.batch.remove(&write.partitions.index, key);
+            }
+        }
+
+        // Iteration
+
+        pub fn iterate(
+            read: &Read<'_>,
+            position: Option<Position>,
+            tag_key: u64,
+        ) -> impl Iterator<Item = u64> + use<> {
+            let encoding = read.partitions.encoding;
+
+            let lower = write_key(encoding, position.unwrap_or(Position::from(0u64)), tag_key);
+            let upper = write_key(encoding, Position::from(u64::MAX), tag_key);
+
+            read.partitions.index.range(lower..=upper).map(|key_value| {
+                let (key, _): (Slice, Slice) =
+                    key_value.expect("invalid key/value during iteration");
+
+                let mut key = &key[key.len() - POSITION_LEN..];
+
+                key.get_u64()
+            })
+        }
+
+        // Maintenance
+
+        pub fn backfill(
+            database: &Database,
+            partitions: &Partitions,
+            hasher: Hasher,
+        ) -> Result<(), Box<dyn Error>> {
+            let read = Read::new(partitions);
+            let encoding = partitions.encoding;
+            let mut batch = database.batch();
+
+            for position in data::iterate(&read, None) {
+                let position = Position::from(position);
+                let record = data::get(&read, position)?.ok_or("event missing during backfill")?;
+
+                for tag in record.tags {
+                    let tag = reference::tag(&read, tag)?;
+
+                    if let Some(tag_key) = tag.key() {
+                        let tag_key = match encoding {
+                            Encoding::Hashed => hasher.hash(tag_key),
+                            Encoding::Dictionary => reference::tag_key(&read, tag_key)?,
+                        };
+
+                        let key = write_key(encoding, position, tag_key);
+
+                        batch.insert(&partitions.index, key, []);
+                    }
+                }
+
+                if batch.len() >= BACKFILL_BATCH_LEN {
+                    batch.commit()?;
+                    batch = database.batch();
+                }
+            }
+
+            batch.commit()?;
+
+            Ok(())
         }
+
+        // Keys/Prefixes
+
+        fn write_key(encoding: Encoding, position: Position, tag_key: u64) -> Vec<u8> {
+            let mut key = Vec::with_capacity(KEY_LEN);
+
+            key.put_u8(INDEX_ID);
+            encoding.put_id(&mut key, tag_key);
+            key.put_u64(position.value());
+
+            key
+        }
+    }
+
+    // Ordered Index
+
+    mod ordered {
+        use std::{
+            collections::BTreeSet,
+            error::Error,
+        };
+
+        use bytes::{
+            Buf as _,
+            BufMut as _,
+        };
+
+        use crate::{
+            model::Position,
+            persistence::{
+                Database,
+                HashedTag,
+                POSITION_LEN,
+                Partitions,
+                Read,
+                Write,
+                data,
+                reference,
+            },
+        };
+
+        static BACKFILL_BATCH_LEN: usize = 1024;
+        static INDEX_ID: u8 = 2;
+        static SEPARATOR: u8 = 0;
+
+        // Insertion
+
+        pub fn insert(write: &mut Write<'_>, position: Position, tags: &[HashedTag]) {
+            for tag in tags {
+                let key = write_key(position, tag.value());
+
+                write.batch.insert(&write.partitions.index, key, []);
+            }
+        }
+
+        // Removal
+
+        pub fn remove(write: &mut Write<'_>, position: Position, tags: &[HashedTag]) {
+            for tag in tags {
+                let key = write_key(position, tag.value());
+
+                write.batch.remove(&write.partitions.index, key);
+            }
+        }
+
+        // Iteration
+
+        pub fn positions(read: &Read<'_>, prefix: &str) -> BTreeSet<u64> {
+            read.partitions
+                .index
+                .prefix(write_prefix(prefix))
+                .map(|key_value| {
+                    let (key, _) = key_value.expect("invalid key/value during iteration");
+
+                    let mut key = &key[key.len() - POSITION_LEN..];
+
+                    key.get_u64()
+                })
+                .collect()
+        }
+
+        // Maintenance
+
+        pub fn backfill(
+            database: &Database,
+            partitions: &Partitions,
+        ) -> Result<(), Box<dyn Error>> {
+            let read = Read::new(partitions);
+            let mut batch = database.batch();
+
+            for position in data::iterate(&read, None) {
+                let position = Position::from(position);
+                let record = data::get(&read, position)?.ok_or("event missing during backfill")?;
+
+                for tag in record.tags {
+                    let tag = reference::tag(&read, tag)?;
+                    let key = write_key(position, tag.value());
+
+                    batch.insert(&partitions.index, key, []);
+                }
+
+                if batch.len() >= BACKFILL_BATCH_LEN {
+                    batch.commit()?;
+                    batch = database.batch();
+                }
+            }
+
+            batch.commit()?;
+
+            Ok(())
+        }
+
+        // Keys/Prefixes
+
+        fn write_key(position: Position, tag: &str) -> Vec<u8> {
+            let mut key = write_prefix(tag);
+
+            key.put_u8(SEPARATOR);
+            key.put_u64(position.value());
+
+            key
+        }
+
+        fn write_prefix(prefix: &str) -> Vec<u8> {
+            let mut key = Vec::with_capacity(1 + prefix.len() + 1 + POSITION_LEN);
+
+            key.put_u8(INDEX_ID);
+            key.put_slice(prefix.as_bytes());
+
+            key
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Extracted
+
+mod extracted {
+    use std::{
+        collections::BTreeSet,
+        error::Error,
+        ops::Bound,
+    };
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+
+    use crate::{
+        model::{
+            IndexKey,
+            Position,
+        },
+        options::Extractor,
+        persistence::{
+            self,
+            Database,
+            HashedIndexRange,
+            POSITION_LEN,
+            Partitions,
+            Read,
+            Write,
+            data,
+            index::ID_LEN,
+        },
+    };
+
+    static BATCH_LEN: usize = 1024;
+    static ESCAPE: u8 = 0xff;
+    static HASH_LEN: usize = size_of::<u64>();
+    static INDEX_ID: u8 = 4;
+    static TERMINATOR: [u8; 2] = [0, 0];
+
+    // Insertion
+
+    pub fn insert(write: &mut Write<'_>, position: Position, indexes: &[(u64, IndexKey)]) {
+        for (name, key) in indexes {
+            let key = write_key(*name, key, position);
+
+            write.batch.insert(&write.partitions.index, key, []);
+        }
+    }
+
+    // Removal
+
+    pub fn remove(write: &mut Write<'_>, position: Position, indexes: &[(u64, IndexKey)]) {
+        for (name, key) in indexes {
+            let key = write_key(*name, key, position);
+
+            write.batch.remove(&write.partitions.index, key);
+        }
+    }
+
+    // Iteration
+
+    pub fn positions(read: &Read<'_>, range: &HashedIndexRange) -> BTreeSet<u64> {
+        let name = range.hash();
+        let prefix = write_prefix(name);
+        let (lower, upper) = range.bounds();
+
+        let lower = match lower {
+            Bound::Included(key) => Bound::Included(write_value(name, key)),
+            Bound::Excluded(key) => Bound::Excluded(write_key(name, key, Position::from(u64::MAX))),
+            Bound::Unbounded => Bound::Included(prefix.clone()),
+        };
+
+        let upper = match upper {
+            Bound::Included(key) => Bound::Included(write_key(name, key, Position::from(u64::MAX))),
+            Bound::Excluded(key) => Bound::Excluded(write_value(name, key)),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        read.partitions
+            .index
+            .range((lower, Bound::<Vec<u8>>::Unbounded))
+            .map(|key_value| {
+                let (key, _) = key_value.expect("invalid key/value during iteration");
+
+                key
+            })
+            .take_while(|key| {
+                key.starts_with(&prefix)
+                    && match &upper {
+                        Bound::Included(upper) => key[..] <= upper[..],
+                        Bound::Excluded(upper) => key[..] < upper[..],
+                        Bound::Unbounded => true,
+                    }
+            })
+            .map(|key| {
+                let mut key = &key[key.len() - POSITION_LEN..];
+
+                key.get_u64()
+            })
+            .collect()
+    }
+
+    // Maintenance
+
+    pub fn backfill(
+        database: &Database,
+        partitions: &Partitions,
+        name: u64,
+        extractor: &Extractor,
+    ) -> Result<(), Box<dyn Error>> {
+        let read = Read::new(partitions);
+        let mut batch = database.batch();
+
+        for position in data::iterate(&read, None) {
+            let position = Position::from(position);
+            let event =
+                persistence::get(&read, position)?.ok_or("event missing during backfill")?;
+
+            if event.archived.is_some() {
+                continue;
+            }
+
+            for key in extractor.extract(&event.event) {
+                let key = write_key(name, &key, position);
+
+                batch.insert(&partitions.index, key, []);
+            }
+
+            if batch.len() >= BATCH_LEN {
+                batch.commit()?;
+                batch = database.batch();
+            }
+        }
+
+        batch.commit()?;
+
+        Ok(())
+    }
+
+    pub fn clear(
+        database: &Database,
+        partitions: &Partitions,
+        name: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut batch = database.batch();
+
+        for key_value in partitions.index.prefix(write_prefix(name)) {
+            let (key, _) = key_value?;
+
+            batch.remove(&partitions.index, key);
+
+            if batch.len() >= BATCH_LEN {
+                batch.commit()?;
+                batch = database.batch();
+            }
+        }
+
+        batch.commit()?;
+
+        Ok(())
+    }
+
+    // Keys/Prefixes
+
+    fn write_key(name: u64, key: &IndexKey, position: Position) -> Vec<u8> {
+        let mut value = write_value(name, key);
+
+        value.put_u64(position.value());
+
+        value
+    }
+
+    fn write_value(name: u64, key: &IndexKey) -> Vec<u8> {
+        let mut value = write_prefix(name);
+
+        for byte in key.value() {
+            value.put_u8(*byte);
+
+            if *byte == 0 {
+                value.put_u8(ESCAPE);
+            }
+        }
+
+        value.put_slice(&TERMINATOR);
+
+        value
+    }
+
+    fn write_prefix(name: u64) -> Vec<u8> {
+        let mut prefix = Vec::with_capacity(ID_LEN + HASH_LEN);
+
+        prefix.put_u8(INDEX_ID);
+        prefix.put_u64(name);
+
+        prefix
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Time
+
+mod time {
+    use std::error::Error;
+
+    use crate::{
+        model::Position,
+        persistence::{
+            self,
+            Database,
+            Partitions,
+            Read,
+            Write,
+            data,
+        },
+    };
+
+    static BACKFILL_BATCH_LEN: usize = 1024;
+    static TIMESTAMP_LEN: usize = size_of::<u64>();
+
+    // Insertion
+
+    pub fn insert(write: &mut Write<'_>, position: Position, timestamp: u64) {
+        forward::insert(write, position, timestamp);
+        reverse::insert(write, position, timestamp);
+    }
+
+    // Removal
+
+    pub fn remove(write: &mut Write<'_>, position: Position) -> Result<(), Box<dyn Error>> {
+        if let Some(timestamp) = reverse::get(write.partitions, position)? {
+            forward::remove(write, position, timestamp);
+            reverse::remove(write, position);
+        }
+
+        Ok(())
+    }
+
+    // Iteration
+
+    pub fn iterate(read: &Read<'_>, timestamp: u64) -> impl Iterator<Item = u64> + use<> {
+        forward::iterate(read, timestamp)
+    }
+
+    // Maintenance
+
+    pub fn backfill(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+        let read = Read::new(partitions);
+        let timestamp = persistence::timestamp();
+        let mut batch = database.batch();
+
+        for position in data::iterate(&read, None) {
+            let position = Position::from(position);
+            let record = data::get(&read, position)?.ok_or("event missing during backfill")?;
+
+            if record.archived.is_some() || reverse::get(partitions, position)?.is_some() {
+                continue;
+            }
+
+            insert(&mut Write::new(&mut batch, partitions), position, timestamp);
+
+            if batch.len() >= BACKFILL_BATCH_LEN {
+                batch.commit()?;
+                batch = database.batch();
+            }
+        }
+
+        batch.commit()?;
+
+        Ok(())
+    }
+
+    // Forward Index
+
+    mod forward {
+        use std::collections::BTreeSet;
+
+        use bytes::{
+            Buf as _,
+            BufMut as _,
+        };
+
+        use crate::{
+            model::Position,
+            persistence::{
+                POSITION_LEN,
+                Read,
+                Write,
+                index::{
+                    ID_LEN,
+                    time::TIMESTAMP_LEN,
+                },
+            },
+        };
+
+        static INDEX_ID: u8 = 5;
+        static KEY_LEN: usize = ID_LEN + TIMESTAMP_LEN + POSITION_LEN;
+
+        // Insertion
+
+        pub fn insert(write: &mut Write<'_>, position: Position, timestamp: u64) {
+            let mut key = [0u8; KEY_LEN];
+
+            write_key(&mut key, position, timestamp);
+
+            write.batch.insert(&write.partitions.index, key, []);
+        }
+
+        // Removal
+
+        pub fn remove(write: &mut Write<'_>, position: Position, timestamp: u64) {
+            let mut key = [0u8; KEY_LEN];
+
+            write_key(&mut key, position, timestamp);
+
+            write.batch.remove(&write.partitions.index, key);
+        }
+
+        // Iteration
+
+        pub fn iterate(read: &Read<'_>, timestamp: u64) -> impl Iterator<Item = u64> + use<> {
+            let mut lower = [0u8; KEY_LEN];
+            let mut upper = [0u8; KEY_LEN];
+
+            write_key(&mut lower, Position::from(0u64), 0);
+            write_key(&mut upper, Position::from(0u64), timestamp);
+
+            let positions = read
+                .partitions
+                .index
+                .range(lower..upper)
+                .map(|key_value| {
+                    let (key, _) = key_value.expect("invalid key/value during iteration");
+
+                    let mut key = &key[ID_LEN + TIMESTAMP_LEN..];
+
+                    key.get_u64()
+                })
+                .collect::<BTreeSet<_>>();
+
+            positions.into_iter()
+        }
+
+        // Keys/Prefixes
+
+        fn write_key(key: &mut [u8; KEY_LEN], position: Position, timestamp: u64) {
+            let mut key = &mut key[..];
+
+            key.put_u8(INDEX_ID);
+            key.put_u64(timestamp);
+            key.put_u64(position.value());
+        }
+    }
+
+    // Reverse Index
+
+    mod reverse {
+        use std::error::Error;
+
+        use bytes::{
+            Buf as _,
+            BufMut as _,
+        };
+
+        use crate::{
+            model::Position,
+            persistence::{
+                POSITION_LEN,
+                Partitions,
+                Write,
+                index::ID_LEN,
+            },
+        };
+
+        static INDEX_ID: u8 = 6;
+        static KEY_LEN: usize = ID_LEN + POSITION_LEN;
+
+        // Retrieval
+
+        pub fn get(
+            partitions: &Partitions,
+            position: Position,
+        ) -> Result<Option<u64>, Box<dyn Error>> {
+            let mut key = [0u8; KEY_LEN];
+
+            write_key(&mut key, position);
+
+            let value = partitions.index.get(key)?;
+
+            Ok(value.map(|value| value.as_ref().get_u64()))
+        }
+
+        // Insertion
+
+        pub fn insert(write: &mut Write<'_>, position: Position, timestamp: u64) {
+            let mut key = [0u8; KEY_LEN];
+
+            write_key(&mut key, position);
+
+            write
+                .batch
+                .insert(&write.partitions.index, key, timestamp.to_be_bytes());
+        }
+
+        // Removal
+
+        pub fn remove(write: &mut Write<'_>, position: Position) {
+            let mut key = [0u8; KEY_LEN];
+
+            write_key(&mut key, position);
+
+            write.batch.remove(&write.partitions.index, key);
+        }
+
+        // Keys/Prefixes
+
+        fn write_key(key: &mut [u8; KEY_LEN], position: Position) {
+            let mut key = &mut key[..];
+
+            key.put_u8(INDEX_ID);
+            key.put_u64(position.value());
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Merge
+
+mod merge {
+    use std::{
+        collections::BTreeSet,
+        iter::{
+            self,
+            Peekable,
+        },
+        ops::Bound,
+        sync::Arc,
+    };
+
+    use crate::model::Position;
+
+    pub type Seek = Box<dyn Fn(Option<Position>) -> Box<dyn Iterator<Item = u64>>>;
+
+    static SCAN_LEN: usize = 32;
+
+    // Union
+
+    pub fn union(iterators: Vec<Box<dyn Iterator<Item = u64>>>) -> impl Iterator<Item = u64> {
+        let mut iterators = iterators
+            .into_iter()
+            .map(Iterator::peekable)
+            .collect::<Vec<_>>();
+
+        iter::from_fn(move || {
+            let next = iterators
+                .iter_mut()
+                .filter_map(|iterator| iterator.peek().copied())
+                .min()?;
+
+            for iterator in &mut iterators {
+                iterator.next_if_eq(&next);
+            }
+
+            Some(next)
+        })
+    }
+
+    // Within
+
+    pub fn within(
+        positions: Arc<BTreeSet<u64>>,
+        position: Option<Position>,
+    ) -> impl Iterator<Item = u64> {
+        let mut lower = Bound::Included(position.map_or(0, Position::value));
+
+        iter::from_fn(move || {
+            let next = *positions.range((lower, Bound::Unbounded)).next()?;
+
+            lower = Bound::Excluded(next);
+
+            Some(next)
+        })
+    }
+
+    // Intersection
+
+    pub fn intersection(position: Option<Position>, seeks: Vec<Seek>) -> impl Iterator<Item = u64> {
+        let mut cursors = seeks
+            .into_iter()
+            .map(|seek| Cursor::new(seek, position))
+            .collect::<Vec<_>>();
+
+        iter::from_fn(move || {
+            let (lead, others) = cursors.split_first_mut()?;
+            let mut target = lead.peek()?;
+
+            'candidates: loop {
+                for cursor in others.iter_mut() {
+                    let next = cursor.seek(target)?;
+
+                    if next > target {
+                        target = lead.seek(next)?;
+
+                        continue 'candidates;
+                    }
+                }
+
+                lead.iterator.next();
+
+                return Some(target);
+            }
+        })
+    }
+
+    // Cursor
+
+    struct Cursor {
+        seek: Seek,
+        iterator: Peekable<Box<dyn Iterator<Item = u64>>>,
+    }
+
+    impl Cursor {
+        fn new(seek: Seek, position: Option<Position>) -> Self {
+            let iterator = seek(position).peekable();
+
+            Self { seek, iterator }
+        }
+
+        fn peek(&mut self) -> Option<u64> {
+            self.iterator.peek().copied()
+        }
+
+        fn seek(&mut self, target: u64) -> Option<u64> {
+            for _ in 0..SCAN_LEN {
+                if self
+                    .iterator
+                    .next_if(|position| *position < target)
+                    .is_none()
+                {
+                    return self.peek();
+                }
+            }
+
+            if self.peek()? < target {
+                self.iterator = (self.seek)(Some(Position::from(target))).peekable();
+            }
+
+            self.peek()
+        }
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeSet,
+        error::Error,
+        sync::Arc,
+    };
+
+    use crate::{
+        model::{
+            Event,
+            IndexKey,
+            IndexRange,
+            Position,
+            Query,
+            Selector,
+            Specifier,
+            Stream,
+            Tag,
+            TagKey,
+        },
+        options::StreamOptions,
+        persistence::index::merge,
+    };
+
+    fn stream() -> Result<Stream, Box<dyn Error>> {
+        let options = StreamOptions::default()
+            .prefix_index(true)
+            .extractor("amount", |event: &Event| {
+                vec![IndexKey::from(u64::from(event.data[0]))]
+            });
+
+        let mut stream = Stream::memory(options)?;
+
+        stream.append((0..120u8).map(|index| {
+            let region = if index % 2 == 0 {
+                "region:eu"
+            } else {
+                "region:us"
+            };
+            let customer = format!("customer:{}", index % 3);
+
+            Event::new(vec![index], ("order-placed", 0), [
+                Tag::from(region),
+                Tag::from(customer),
+            ])
+        }))?;
+
+        Ok(stream)
+    }
+
+    fn positions(
+        stream: &Stream,
+        selector: Selector,
+        position: Option<Position>,
+    ) -> Result<Vec<u64>, Box<dyn Error>> {
+        stream
+            .query(Query::from([selector]), position)
+            .map(|event| event.map(|event| event.position.value()))
+            .collect()
+    }
+
+    #[test]
+    fn within_seeks_materialised_positions() {
+        let positions = Arc::new(BTreeSet::from([1, 3, 5, 8]));
+
+        let all = merge::within(positions.clone(), None).collect::<Vec<_>>();
+        let sought = merge::within(positions, Some(Position::from(4u64))).collect::<Vec<_>>();
+
+        assert_eq!(all, [1, 3, 5, 8]);
+        assert_eq!(sought, [5, 8]);
+    }
+
+    #[test]
+    fn prefix_selectors_intersect_with_tags() -> Result<(), Box<dyn Error>> {
+        let stream = stream()?;
+        let selector = Selector::from((
+            Vec::<Specifier>::new(),
+            [Tag::from("customer:0")],
+            [String::from("region:e")],
+            Vec::<TagKey>::new(),
+        ));
+
+        let expected = (0..120).filter(|index| index % 6 == 0).collect::<Vec<_>>();
+        let later = expected
+            .iter()
+            .copied()
+            .filter(|index| *index >= 50)
+            .collect::<Vec<_>>();
+
+        assert_eq!(positions(&stream, selector.clone(), None)?, expected);
+        assert_eq!(
+            positions(&stream, selector, Some(Position::from(50u64)))?,
+            later
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn extracted_ranges_intersect_with_tags() -> Result<(), Box<dyn Error>> {
+        let stream = stream()?;
+        let range = IndexRange::from(("amount", IndexKey::from(10u64)..IndexKey::from(70u64)));
+        let selector = Selector::from((
+            Vec::<Specifier>::new(),
+            [Tag::from("customer:1")],
+            Vec::<String>::new(),
+            Vec::<TagKey>::new(),
+            [range],
+        ));
+
+        let expected = (10..70).filter(|index| index % 3 == 1).collect::<Vec<_>>();
+
+        assert_eq!(positions(&stream, selector, None)?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tag_key_selectors_match_keys_and_values() -> Result<(), Box<dyn Error>> {
+        let mut stream = Stream::memory(StreamOptions::default())?;
+
+        stream.append([
+            Event::new(vec![0], ("order-placed", 0), [Tag::keyed("student", "1")]),
+            Event::new(vec![1], ("order-placed", 0), [Tag::keyed("student", "2")]),
+            Event::new(vec![2], ("order-placed", 0), [
+                Tag::keyed("student", "3"),
+                Tag::from("vip"),
+            ]),
+            Event::new(vec![3], ("order-placed", 0), [Tag::keyed("course", "1")]),
+            Event::new(vec![4], ("order-placed", 0), [Tag::from("student:4")]),
+        ])?;
+
+        let selector = |tags: Vec<Tag>, tag_keys: Vec<TagKey>| {
+            Selector::from((
+                Vec::<Specifier>::new(),
+                tags,
+                Vec::<String>::new(),
+                tag_keys,
+            ))
+        };
+
+        let any = selector(Vec::new(), vec![TagKey::from("student")]);
+        let values = selector(Vec::new(), vec![TagKey::with_values("student", ["1", "3"])]);
+        let tagged = selector(vec![Tag::from("vip")], vec![TagKey::from("student")]);
+        let missing = selector(Vec::new(), vec![TagKey::from("teacher")]);
+        let plain = selector(vec![Tag::from("student:4")], Vec::new());
+
+        assert_eq!(positions(&stream, any, None)?, [0, 1, 2]);
+        assert_eq!(positions(&stream, values, None)?, [0, 2]);
+        assert_eq!(positions(&stream, tagged, None)?, [2]);
+        assert_eq!(positions(&stream, missing, None)?, Vec::<u64>::new());
+        assert_eq!(positions(&stream, plain, None)?, [4]);
+
+        Ok(())
     }
 }
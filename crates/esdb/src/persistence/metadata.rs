@@ -0,0 +1,885 @@
+use std::{
+    collections::BTreeSet,
+    error::Error,
+};
+
+use bytes::{
+    Buf as _,
+    BufMut as _,
+};
+use fancy_constructor::new;
+
+use crate::{
+    model::{
+        Position,
+        Tag,
+    },
+    persistence::{
+        self,
+        Database,
+        HashedEvent,
+        Partitions,
+        Read,
+        Write,
+        backend::{
+            KvPair,
+            PartitionHandle,
+        },
+    },
+};
+
+// =================================================================================================
+// Metadata
+// =================================================================================================
+
+static CHAINED_KEY: &[u8] = b"chained";
+static CHAINING_KEY: &[u8] = b"chaining";
+static CHECKSUMMED_KEY: &[u8] = b"checksummed";
+static DICTIONARY_KEY: &[u8] = b"dictionary";
+static EXTRACTED_KEY_PREFIX: &[u8] = b"extracted";
+static FORMAT_KEY: &[u8] = b"format";
+static KEY_INDEXED_KEY: &[u8] = b"key-indexed";
+static MIGRATED_KEY: &[u8] = b"migrated";
+static NAMES_INDEXED_KEY: &[u8] = b"names-indexed";
+pub(crate) static PARTITION_NAME: &str = "metadata";
+static PREFIX_INDEXED_KEY: &[u8] = b"prefix-indexed";
+static SEED_KEY: &[u8] = b"seed";
+static TIMESTAMPED_KEY: &[u8] = b"timestamped";
+
+// Partition
+
+pub fn partition(
+    database: &Database,
+    stream: Option<&str>,
+) -> Result<Option<PartitionHandle>, Box<dyn Error>> {
+    persistence::optional_partition(
+        database,
+        stream,
+        PARTITION_NAME,
+        &database.options().reference,
+        true,
+    )
+}
+
+// Backup
+
+pub fn backup(
+    partition: &PartitionHandle,
+    position: Option<Position>,
+) -> impl Iterator<Item = Result<KvPair, Box<dyn Error>>> + use<> {
+    partition
+        .iter()
+        .filter(move |key_value| match (key_value, position) {
+            (Ok((key, _)), Some(_)) => !counts::is_count_key(key) && !usage::is_usage_key(key),
+            _ => true,
+        })
+}
+
+// Retrieval
+
+pub fn is_available(read: &Read<'_>) -> bool {
+    read.partitions.metadata.is_some()
+}
+
+pub fn format(read: &Read<'_>) -> Result<Option<u8>, Box<dyn Error>> {
+    let Some(metadata) = &read.partitions.metadata else {
+        return Ok(None);
+    };
+
+    let value = metadata.get(FORMAT_KEY)?;
+    let format = value.map(|value| value.as_ref().get_u8());
+
+    Ok(format)
+}
+
+pub fn chaining(read: &Read<'_>) -> Result<Option<u64>, Box<dyn Error>> {
+    let Some(metadata) = &read.partitions.metadata else {
+        return Ok(None);
+    };
+
+    let value = metadata.get(CHAINING_KEY)?;
+    let chaining = value.map(|value| value.as_ref().get_u64());
+
+    Ok(chaining)
+}
+
+pub fn migrated(read: &Read<'_>) -> Result<Option<u64>, Box<dyn Error>> {
+    let Some(metadata) = &read.partitions.metadata else {
+        return Ok(None);
+    };
+
+    let value = metadata.get(MIGRATED_KEY)?;
+    let migrated = value.map(|value| value.as_ref().get_u64());
+
+    Ok(migrated)
+}
+
+pub fn seed(read: &Read<'_>) -> Result<Option<u64>, Box<dyn Error>> {
+    let Some(metadata) = &read.partitions.metadata else {
+        return Ok(None);
+    };
+
+    let value = metadata.get(SEED_KEY)?;
+    let seed = value.map(|value| value.as_ref().get_u64());
+
+    Ok(seed)
+}
+
+pub fn extracted(read: &Read<'_>) -> Result<BTreeSet<u64>, Box<dyn Error>> {
+    let Some(metadata) = &read.partitions.metadata else {
+        return Ok(BTreeSet::new());
+    };
+
+    metadata
+        .prefix(EXTRACTED_KEY_PREFIX)
+        .map(|key_value| {
+            let (key, _) = key_value?;
+
+            let mut key = &key[EXTRACTED_KEY_PREFIX.len()..];
+
+            Ok(key.get_u64())
+        })
+        .collect()
+}
+
+pub fn is_chained(read: &Read<'_>) -> Result<bool, Box<dyn Error>> {
+    contains_key(read, CHAINED_KEY)
+}
+
+pub fn is_checksummed(read: &Read<'_>) -> Result<bool, Box<dyn Error>> {
+    contains_key(read, CHECKSUMMED_KEY)
+}
+
+pub fn is_dictionary(read: &Read<'_>) -> Result<bool, Box<dyn Error>> {
+    contains_key(read, DICTIONARY_KEY)
+}
+
+pub fn is_key_indexed(read: &Read<'_>) -> Result<bool, Box<dyn Error>> {
+    contains_key(read, KEY_INDEXED_KEY)
+}
+
+pub fn is_names_indexed(read: &Read<'_>) -> Result<bool, Box<dyn Error>> {
+    contains_key(read, NAMES_INDEXED_KEY)
+}
+
+pub fn is_prefix_indexed(read: &Read<'_>) -> Result<bool, Box<dyn Error>> {
+    contains_key(read, PREFIX_INDEXED_KEY)
+}
+
+pub fn is_timestamped(read: &Read<'_>) -> Result<bool, Box<dyn Error>> {
+    contains_key(read, TIMESTAMPED_KEY)
+}
+
+fn contains_key(read: &Read<'_>, key: &[u8]) -> Result<bool, Box<dyn Error>> {
+    match &read.partitions.metadata {
+        Some(metadata) => metadata.contains_key(key),
+        None => Ok(false),
+    }
+}
+
+pub fn identifier_count(read: &Read<'_>, hash: u64) -> Result<Option<u64>, Box<dyn Error>> {
+    counts::get(read, counts::IDENTIFIER_ID, hash)
+}
+
+pub fn identifier_counts(read: &Read<'_>) -> Result<Vec<(u64, u64)>, Box<dyn Error>> {
+    counts::all(read, counts::IDENTIFIER_ID)
+}
+
+pub fn tag_count(read: &Read<'_>, hash: u64) -> Result<Option<u64>, Box<dyn Error>> {
+    counts::get(read, counts::TAG_ID, hash)
+}
+
+pub fn tag_counts(read: &Read<'_>) -> Result<Vec<(u64, u64)>, Box<dyn Error>> {
+    counts::all(read, counts::TAG_ID)
+}
+
+pub fn storage_usage(read: &Read<'_>, namespace: &str) -> Result<u64, Box<dyn Error>> {
+    usage::storage(read, namespace).map(Option::unwrap_or_default)
+}
+
+pub fn daily_usage(read: &Read<'_>, namespace: &str, day: u64) -> Result<u64, Box<dyn Error>> {
+    usage::daily(read, namespace, day)
+}
+
+// Insertion
+
+pub fn insert(write: &mut Write<'_>, event: &HashedEvent) {
+    counts::adjust(
+        write,
+        counts::IDENTIFIER_ID,
+        event.descriptor.identifer().hash(),
+        1,
+    );
+
+    for tag in &event.tags {
+        counts::adjust(write, counts::TAG_ID, tag.hash(), 1);
+    }
+
+    let bytes = event.data.len() as u64;
+
+    usage::adjust(write, event, Pending::new(1, bytes.cast_signed(), bytes));
+}
+
+pub fn insert_chained(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, CHAINED_KEY, []);
+
+    Ok(())
+}
+
+pub fn insert_chaining(write: &mut Write<'_>, position: u64) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write
+        .batch
+        .insert(metadata, CHAINING_KEY, position.to_be_bytes());
+
+    Ok(())
+}
+
+pub fn insert_checksummed(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, CHECKSUMMED_KEY, []);
+
+    Ok(())
+}
+
+pub fn insert_dictionary(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, DICTIONARY_KEY, []);
+
+    Ok(())
+}
+
+pub fn insert_extracted(write: &mut Write<'_>, name: u64) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, extracted_key(name), []);
+
+    Ok(())
+}
+
+pub fn insert_key_indexed(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, KEY_INDEXED_KEY, []);
+
+    Ok(())
+}
+
+pub fn insert_names_indexed(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, NAMES_INDEXED_KEY, []);
+
+    Ok(())
+}
+
+pub fn insert_prefix_indexed(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, PREFIX_INDEXED_KEY, []);
+
+    Ok(())
+}
+
+pub fn insert_format(write: &mut Write<'_>, format: u8) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, FORMAT_KEY, [format]);
+
+    Ok(())
+}
+
+pub fn insert_migrated(write: &mut Write<'_>, position: u64) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write
+        .batch
+        .insert(metadata, MIGRATED_KEY, position.to_be_bytes());
+
+    Ok(())
+}
+
+pub fn insert_seed(write: &mut Write<'_>, seed: u64) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, SEED_KEY, seed.to_be_bytes());
+
+    Ok(())
+}
+
+pub fn insert_timestamped(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.insert(metadata, TIMESTAMPED_KEY, []);
+
+    Ok(())
+}
+
+// Removal
+
+pub fn remove(write: &mut Write<'_>, event: &HashedEvent) {
+    counts::adjust(
+        write,
+        counts::IDENTIFIER_ID,
+        event.descriptor.identifer().hash(),
+        -1,
+    );
+
+    for tag in &event.tags {
+        counts::adjust(write, counts::TAG_ID, tag.hash(), -1);
+    }
+
+    let bytes = event.data.len() as u64;
+
+    usage::adjust(write, event, Pending::new(0, -bytes.cast_signed(), 0));
+}
+
+pub fn remove_chaining(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.remove(metadata, CHAINING_KEY);
+
+    Ok(())
+}
+
+pub fn remove_extracted(write: &mut Write<'_>, name: u64) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.remove(metadata, extracted_key(name));
+
+    Ok(())
+}
+
+pub fn remove_migrated(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.remove(metadata, MIGRATED_KEY);
+
+    Ok(())
+}
+
+pub fn remove_timestamped(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+    let metadata = metadata(write.partitions)?;
+
+    write.batch.remove(metadata, TIMESTAMPED_KEY);
+
+    Ok(())
+}
+
+// Redaction
+
+pub fn redact(write: &mut Write<'_>, event: &HashedEvent, replacement: &HashedEvent) {
+    let bytes = replacement.data.len() as u64;
+    let removed = event.data.len() as u64;
+
+    usage::adjust(
+        write,
+        event,
+        Pending::new(0, bytes.cast_signed() - removed.cast_signed(), 0),
+    );
+}
+
+// Maintenance
+
+pub fn flush(write: &mut Write<'_>) -> Result<Vec<u64>, Box<dyn Error>> {
+    usage::flush(write)?;
+    counts::flush(write)
+}
+
+pub fn recount(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+    counts::recount(database, partitions)
+}
+
+pub fn measure(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+    usage::measure(database, partitions)
+}
+
+pub fn pending_usage(write: &Write<'_>, namespace: &str) -> Pending {
+    write.usage.get(namespace).copied().unwrap_or_default()
+}
+
+// Namespaces
+
+pub fn contains(namespace: &str, tag: &Tag) -> bool {
+    usage::contains(namespace, tag)
+}
+
+pub fn day(timestamp: u64) -> u64 {
+    usage::day(timestamp)
+}
+
+// Metadata
+
+fn metadata(partitions: &Partitions) -> Result<&PartitionHandle, Box<dyn Error>> {
+    partitions
+        .metadata
+        .as_ref()
+        .ok_or_else(|| "metadata partition unavailable".into())
+}
+
+// Keys/Prefixes
+
+fn extracted_key(name: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(EXTRACTED_KEY_PREFIX.len() + size_of::<u64>());
+
+    key.put_slice(EXTRACTED_KEY_PREFIX);
+    key.put_u64(name);
+
+    key
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Pending
+
+#[derive(new, Clone, Copy, Debug, Default)]
+#[new(vis())]
+pub struct Pending {
+    pub events: u64,
+    pub storage: i64,
+    pub daily: u64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Counts
+
+mod counts {
+    use std::{
+        collections::BTreeMap,
+        error::Error,
+        mem,
+    };
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+
+    use crate::{
+        model::Position,
+        persistence::{
+            Database,
+            Partitions,
+            Read,
+            Write,
+            data,
+            metadata,
+        },
+    };
+
+    pub static IDENTIFIER_ID: u8 = 0;
+    pub static TAG_ID: u8 = 1;
+
+    static COUNTED_KEY: &[u8] = b"counted";
+    static HASH_LEN: usize = size_of::<u64>();
+    static ID_LEN: usize = size_of::<u8>();
+    static KEY_PREFIX: &[u8] = b"count";
+    static KEY_LEN: usize = KEY_PREFIX.len() + ID_LEN + HASH_LEN;
+    static PREFIX_LEN: usize = KEY_PREFIX.len() + ID_LEN;
+    static RECOUNT_BATCH_LEN: usize = 1024;
+
+    // Retrieval
+
+    pub fn get(read: &Read<'_>, id: u8, hash: u64) -> Result<Option<u64>, Box<dyn Error>> {
+        if !is_counted(read)? {
+            return Ok(None);
+        }
+
+        let Some(metadata) = &read.partitions.metadata else {
+            return Ok(None);
+        };
+
+        let mut key = [0u8; KEY_LEN];
+
+        write_key(&mut key, id, hash);
+
+        let value = metadata.get(key)?;
+        let count = value.map_or(0, |value| value.as_ref().get_u64());
+
+        Ok(Some(count))
+    }
+
+    pub fn all(read: &Read<'_>, id: u8) -> Result<Vec<(u64, u64)>, Box<dyn Error>> {
+        let Some(metadata) = &read.partitions.metadata else {
+            return Ok(Vec::new());
+        };
+
+        let mut prefix = [0u8; PREFIX_LEN];
+
+        write_prefix(&mut prefix, id);
+
+        metadata
+            .prefix(prefix)
+            .map(|key_value| {
+                let (key, value) = key_value?;
+
+                let mut key = &key[PREFIX_LEN..];
+
+                Ok((key.get_u64(), value.as_ref().get_u64()))
+            })
+            .collect()
+    }
+
+    fn is_counted(read: &Read<'_>) -> Result<bool, Box<dyn Error>> {
+        metadata::contains_key(read, COUNTED_KEY)
+    }
+
+    // Insertion
+
+    pub fn adjust(write: &mut Write<'_>, id: u8, hash: u64, delta: i64) {
+        *write.counts.entry((id, hash)).or_default() += delta;
+    }
+
+    pub fn flush(write: &mut Write<'_>) -> Result<Vec<u64>, Box<dyn Error>> {
+        let counts = mem::take(&mut write.counts);
+        let metadata = metadata::metadata(write.partitions)?;
+        let mut key = [0u8; KEY_LEN];
+        let mut unused = Vec::new();
+
+        for ((id, hash), delta) in counts {
+            write_key(&mut key, id, hash);
+
+            let value = metadata.get(key)?;
+            let count = value.map_or(0, |value| value.as_ref().get_u64());
+            let count = count.saturating_add_signed(delta);
+
+            if count > 0 {
+                write.batch.insert(metadata, key, count.to_be_bytes());
+            } else {
+                write.batch.remove(metadata, key);
+
+                if id == TAG_ID {
+                    unused.push(hash);
+                }
+            }
+        }
+
+        Ok(unused)
+    }
+
+    // Maintenance
+
+    pub fn recount(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+        let read = Read::new(partitions);
+
+        if is_counted(&read)? {
+            return Ok(());
+        }
+
+        let mut counts = BTreeMap::<(u8, u64), u64>::new();
+
+        for position in data::iterate(&read, None) {
+            let record = data::get(&read, Position::from(position))?
+                .ok_or("event missing during recount")?;
+
+            if record.archived.is_some() {
+                continue;
+            }
+
+            *counts
+                .entry((IDENTIFIER_ID, record.identifier))
+                .or_default() += 1;
+
+            for tag in record.tags {
+                *counts.entry((TAG_ID, tag)).or_default() += 1;
+            }
+        }
+
+        let metadata = metadata::metadata(partitions)?;
+        let mut batch = database.batch();
+        let mut key = [0u8; KEY_LEN];
+
+        for ((id, hash), count) in counts {
+            write_key(&mut key, id, hash);
+
+            batch.insert(metadata, key, count.to_be_bytes());
+
+            if batch.len() >= RECOUNT_BATCH_LEN {
+                batch.commit()?;
+                batch = database.batch();
+            }
+        }
+
+        batch.insert(metadata, COUNTED_KEY, []);
+        batch.commit()?;
+
+        Ok(())
+    }
+
+    // Keys/Prefixes
+
+    pub fn is_count_key(key: &[u8]) -> bool {
+        key.starts_with(KEY_PREFIX)
+    }
+
+    fn write_key(key: &mut [u8; KEY_LEN], id: u8, hash: u64) {
+        let mut key = &mut key[..];
+
+        key.put_slice(KEY_PREFIX);
+        key.put_u8(id);
+        key.put_u64(hash);
+    }
+
+    fn write_prefix(prefix: &mut [u8; PREFIX_LEN], id: u8) {
+        let mut prefix = &mut prefix[..];
+
+        prefix.put_slice(KEY_PREFIX);
+        prefix.put_u8(id);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Usage
+
+mod usage {
+    use std::{
+        collections::{
+            BTreeMap,
+            BTreeSet,
+        },
+        error::Error,
+        mem,
+    };
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+
+    use crate::{
+        model::{
+            Position,
+            Tag,
+        },
+        persistence::{
+            self,
+            Database,
+            HashedEvent,
+            Partitions,
+            Read,
+            Write,
+            data,
+            metadata::{
+                self,
+                Pending,
+            },
+            reference,
+        },
+    };
+
+    static DAILY_ID: u8 = 1;
+    static DAY_MILLIS: u64 = 24 * 60 * 60 * 1000;
+    static ID_LEN: usize = size_of::<u8>();
+    static KEY_PREFIX: &[u8] = b"usage";
+    static PREFIX_LEN: usize = KEY_PREFIX.len() + ID_LEN;
+    static STORAGE_ID: u8 = 0;
+
+    // Retrieval
+
+    pub fn storage(read: &Read<'_>, namespace: &str) -> Result<Option<u64>, Box<dyn Error>> {
+        let Some(metadata) = &read.partitions.metadata else {
+            return Ok(None);
+        };
+
+        let value = metadata.get(key(STORAGE_ID, namespace))?;
+
+        Ok(value.map(|value| value.as_ref().get_u64()))
+    }
+
+    pub fn daily(read: &Read<'_>, namespace: &str, day: u64) -> Result<u64, Box<dyn Error>> {
+        let Some(metadata) = &read.partitions.metadata else {
+            return Ok(0);
+        };
+
+        let value = metadata.get(key(DAILY_ID, namespace))?;
+
+        let bytes = value.map_or(0, |value| {
+            let mut value = value.as_ref();
+
+            if value.get_u64() == day {
+                value.get_u64()
+            } else {
+                0
+            }
+        });
+
+        Ok(bytes)
+    }
+
+    fn namespaces(read: &Read<'_>) -> Result<BTreeSet<String>, Box<dyn Error>> {
+        let Some(metadata) = &read.partitions.metadata else {
+            return Ok(BTreeSet::new());
+        };
+
+        let mut prefix = [0u8; PREFIX_LEN];
+
+        write_prefix(&mut prefix, STORAGE_ID);
+
+        metadata
+            .prefix(prefix)
+            .map(|key_value| {
+                let (key, _) = key_value?;
+
+                Ok(String::from_utf8(key[PREFIX_LEN..].to_vec())?)
+            })
+            .collect()
+    }
+
+    // Insertion
+
+    pub fn adjust(write: &mut Write<'_>, event: &HashedEvent, pending: Pending) {
+        let namespaces = write
+            .partitions
+            .namespaces
+            .iter()
+            .filter(|namespace| event.tags.iter().any(|tag| contains(namespace, tag)));
+
+        for namespace in namespaces {
+            let usage = write.usage.entry(namespace.clone()).or_default();
+
+            usage.events += pending.events;
+            usage.storage += pending.storage;
+            usage.daily += pending.daily;
+        }
+    }
+
+    pub fn flush(write: &mut Write<'_>) -> Result<(), Box<dyn Error>> {
+        let usage = mem::take(&mut write.usage);
+        let metadata = metadata::metadata(write.partitions)?;
+        let read = Read::new(write.partitions);
+        let day = day(persistence::timestamp());
+
+        for (namespace, pending) in usage {
+            let storage = storage(&read, &namespace)?.unwrap_or_default();
+            let storage = storage.saturating_add_signed(pending.storage);
+
+            write
+                .batch
+                .insert(metadata, key(STORAGE_ID, &namespace), storage.to_be_bytes());
+
+            if pending.daily > 0 {
+                let daily = daily(&read, &namespace, day)? + pending.daily;
+
+                let mut value = Vec::with_capacity(2 * size_of::<u64>());
+
+                value.put_u64(day);
+                value.put_u64(daily);
+
+                write
+                    .batch
+                    .insert(metadata, key(DAILY_ID, &namespace), value);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Maintenance
+
+    pub fn measure(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+        let read = Read::new(partitions);
+        let measured = namespaces(&read)?;
+
+        let unmeasured = partitions
+            .namespaces
+            .iter()
+            .filter(|namespace| !measured.contains(*namespace))
+            .collect::<Vec<_>>();
+        let stale = measured
+            .iter()
+            .filter(|namespace| !partitions.namespaces.contains(namespace))
+            .collect::<Vec<_>>();
+
+        if unmeasured.is_empty() && stale.is_empty() {
+            return Ok(());
+        }
+
+        let mut storage = unmeasured
+            .iter()
+            .map(|namespace| (*namespace, 0u64))
+            .collect::<BTreeMap<_, _>>();
+
+        if !unmeasured.is_empty() {
+            for position in data::iterate(&read, None) {
+                let record = data::get(&read, Position::from(position))?
+                    .ok_or("event missing during measurement")?;
+
+                if record.archived.is_some() {
+                    continue;
+                }
+
+                let tags = record
+                    .tags
+                    .iter()
+                    .map(|tag| reference::tag(&read, *tag))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for (namespace, bytes) in &mut storage {
+                    if tags.iter().any(|tag| contains(namespace, tag)) {
+                        *bytes += record.data.len() as u64;
+                    }
+                }
+            }
+        }
+
+        let metadata = metadata::metadata(partitions)?;
+        let mut batch = database.batch();
+
+        for (namespace, bytes) in storage {
+            batch.insert(metadata, key(STORAGE_ID, namespace), bytes.to_be_bytes());
+        }
+
+        for namespace in stale {
+            batch.remove(metadata, key(STORAGE_ID, namespace));
+            batch.remove(metadata, key(DAILY_ID, namespace));
+        }
+
+        batch.commit()?;
+
+        Ok(())
+    }
+
+    // Namespaces
+
+    pub fn contains(namespace: &str, tag: &Tag) -> bool {
+        let key = tag
+            .key()
+            .or_else(|| tag.value().split_once(':').map(|(key, _)| key));
+
+        tag.value() == namespace || key == Some(namespace)
+    }
+
+    pub fn day(timestamp: u64) -> u64 {
+        timestamp / DAY_MILLIS
+    }
+
+    // Keys/Prefixes
+
+    pub fn is_usage_key(key: &[u8]) -> bool {
+        key.starts_with(KEY_PREFIX)
+    }
+
+    fn key(id: u8, namespace: &str) -> Vec<u8> {
+        let mut key = Vec::with_capacity(PREFIX_LEN + namespace.len());
+
+        key.put_slice(KEY_PREFIX);
+        key.put_u8(id);
+        key.put_slice(namespace.as_bytes());
+
+        key
+    }
+
+    fn write_prefix(prefix: &mut [u8; PREFIX_LEN], id: u8) {
+        let mut prefix = &mut prefix[..];
+
+        prefix.put_slice(KEY_PREFIX);
+        prefix.put_u8(id);
+    }
+}
@@ -1,14 +1,32 @@
-use std::error::Error;
-
-use fjall::{
-    PartitionCreateOptions,
-    PartitionHandle,
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    error::Error,
 };
 
-use crate::persistence::{
-    Database,
-    HashedEvent,
-    Write,
+use crate::{
+    model::{
+        Identifier,
+        Position,
+        Tag,
+    },
+    options::Encoding,
+    persistence::{
+        self,
+        Database,
+        HashedEvent,
+        HashedQuery,
+        Partitions,
+        Read,
+        Write,
+        backend::{
+            KeyValues,
+            PartitionHandle,
+        },
+        data,
+    },
 };
 
 // =================================================================================================
@@ -16,14 +34,78 @@ use crate::persistence::{
 // =================================================================================================
 
 static ID_LEN: usize = size_of::<u8>();
-static PARTITION_NAME: &str = "reference";
+pub(crate) static PARTITION_NAME: &str = "reference";
 
 // Partition
 
-pub fn partition(database: &Database) -> Result<PartitionHandle, Box<dyn Error>> {
-    Ok(database
-        .as_ref()
-        .open_partition(PARTITION_NAME, PartitionCreateOptions::default())?)
+pub fn partition(
+    database: &Database,
+    stream: Option<&str>,
+) -> Result<PartitionHandle, Box<dyn Error>> {
+    persistence::partition(
+        database,
+        stream,
+        PARTITION_NAME,
+        &database.options().reference,
+    )
+}
+
+// Backup
+
+pub fn backup(read: &Read<'_>, position: Option<Position>) -> Result<KeyValues, Box<dyn Error>> {
+    let partition = &read.partitions.reference;
+
+    let Some(position) = position else {
+        return Ok(partition.iter());
+    };
+
+    let referenced = Referenced::collect(read, position)?;
+    let entries = partition.iter().filter(move |key_value| match key_value {
+        Ok((key, value)) => referenced.retains(key, value),
+        Err(_) => true,
+    });
+
+    Ok(Box::new(entries))
+}
+
+// Retrieval
+
+pub fn identifier(read: &Read<'_>, hash: u64) -> Result<Identifier, Box<dyn Error>> {
+    descriptor::get(read, hash)?.ok_or_else(|| "missing identifier reference".into())
+}
+
+pub fn tag(read: &Read<'_>, hash: u64) -> Result<Tag, Box<dyn Error>> {
+    tags::get(read, hash)?.ok_or_else(|| "missing tag reference".into())
+}
+
+pub fn identifiers(read: &Read<'_>, prefix: &str) -> Result<Vec<Identifier>, Box<dyn Error>> {
+    if read.partitions.indexed.names {
+        dictionary::identifier_names(read, prefix)
+    } else {
+        descriptor::all(read, prefix)
+    }
+}
+
+pub fn tags(read: &Read<'_>, prefix: &str) -> Result<Vec<Tag>, Box<dyn Error>> {
+    if read.partitions.indexed.names {
+        dictionary::tag_names(read, prefix)
+    } else {
+        tags::all(read, prefix)
+    }
+}
+
+pub fn tag_key(read: &Read<'_>, key: &str) -> Result<u64, Box<dyn Error>> {
+    dictionary::tag_key(read, key)?.ok_or_else(|| "missing tag key reference".into())
+}
+
+// Properties
+
+pub fn identifiers_len(read: &Read<'_>) -> Result<u64, Box<dyn Error>> {
+    descriptor::len(read)
+}
+
+pub fn tags_len(read: &Read<'_>) -> Result<u64, Box<dyn Error>> {
+    tags::len(read)
 }
 
 // Insertion
@@ -31,6 +113,89 @@ pub fn partition(database: &Database) -> Result<PartitionHandle, Box<dyn Error>>
 pub fn insert(write: &mut Write<'_>, event: &HashedEvent) {
     descriptor::insert(write, &event.descriptor);
     tags::insert(write, &event.tags);
+
+    if write.partitions.encoding == Encoding::Hashed {
+        dictionary::insert(write, event);
+    }
+}
+
+// Removal
+
+pub fn remove_tags(write: &mut Write<'_>, tags: &[u64]) -> Result<(), Box<dyn Error>> {
+    dictionary::remove_tags(write, tags)?;
+    tags::remove(write, tags);
+
+    Ok(())
+}
+
+// Maintenance
+
+pub fn backfill_names(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+    dictionary::backfill(database, partitions)
+}
+
+// Dictionary
+
+pub fn assign(write: &mut Write<'_>, event: &mut HashedEvent) -> Result<(), Box<dyn Error>> {
+    dictionary::assign(write, event)
+}
+
+pub fn resolve(read: &Read<'_>, event: &mut HashedEvent) -> Result<(), Box<dyn Error>> {
+    dictionary::resolve(read, event)
+}
+
+pub fn resolve_query(
+    read: &Read<'_>,
+    assigned: Option<&Assigned>,
+    query: &mut HashedQuery,
+) -> Result<(), Box<dyn Error>> {
+    dictionary::resolve_query(read, assigned, query)
+}
+
+// Referenced
+
+#[derive(Debug, Default)]
+struct Referenced {
+    identifiers: BTreeSet<u64>,
+    tags: BTreeSet<u64>,
+    tag_keys: BTreeSet<String>,
+}
+
+impl Referenced {
+    fn collect(read: &Read<'_>, position: Position) -> Result<Self, Box<dyn Error>> {
+        let mut referenced = Self::default();
+
+        for current in data::iterate(read, None).take_while(|current| *current < position.value()) {
+            let record =
+                data::get(read, Position::from(current))?.ok_or("event missing during backup")?;
+
+            referenced.identifiers.insert(record.identifier);
+            referenced.tags.extend(record.tags);
+        }
+
+        for hash in &referenced.tags {
+            if let Some(key) = tag(read, *hash)?.key() {
+                referenced.tag_keys.insert(key.to_owned());
+            }
+        }
+
+        Ok(referenced)
+    }
+
+    fn retains(&self, key: &[u8], value: &[u8]) -> bool {
+        descriptor::retains(key, &self.identifiers)
+            .or_else(|| tags::retains(key, &self.tags))
+            .or_else(|| dictionary::retains(key, value, self))
+            .unwrap_or(true)
+    }
+}
+
+// Assigned
+
+#[derive(Debug, Default)]
+pub struct Assigned {
+    ids: BTreeMap<(u8, String), u64>,
+    sequences: BTreeMap<u8, u64>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -38,13 +203,42 @@ pub fn insert(write: &mut Write<'_>, event: &HashedEvent) {
 // Descriptor
 
 mod descriptor {
-    use crate::persistence::{
-        HashedDescriptor,
-        Write,
+    use std::{
+        collections::BTreeSet,
+        error::Error,
+    };
+
+    use crate::{
+        model::Identifier,
+        persistence::{
+            HashedDescriptor,
+            Read,
+            Write,
+        },
     };
 
     static HASH_LEN: usize = size_of::<u64>();
 
+    // Retrieval
+
+    pub fn get(read: &Read<'_>, hash: u64) -> Result<Option<Identifier>, Box<dyn Error>> {
+        lookup::get(read, hash)
+    }
+
+    pub fn all(read: &Read<'_>, prefix: &str) -> Result<Vec<Identifier>, Box<dyn Error>> {
+        lookup::all(read, prefix)
+    }
+
+    pub fn len(read: &Read<'_>) -> Result<u64, Box<dyn Error>> {
+        lookup::len(read)
+    }
+
+    // Backup
+
+    pub fn retains(key: &[u8], identifiers: &BTreeSet<u64>) -> Option<bool> {
+        lookup::retains(key, identifiers)
+    }
+
     // Insertion
 
     pub fn insert(write: &mut Write<'_>, descriptor: &HashedDescriptor) {
@@ -54,26 +248,87 @@ mod descriptor {
     // Lookup Reference
 
     mod lookup {
-        use bytes::BufMut as _;
+        use std::{
+            collections::BTreeSet,
+            error::Error,
+        };
 
-        use crate::persistence::{
-            HashedDescriptor,
-            Write,
-            reference::{
-                ID_LEN,
-                descriptor::HASH_LEN,
+        use bytes::{
+            Buf as _,
+            BufMut as _,
+        };
+
+        use crate::{
+            model::Identifier,
+            persistence::{
+                HashedDescriptor,
+                Read,
+                Write,
+                reference::{
+                    ID_LEN,
+                    descriptor::HASH_LEN,
+                },
             },
         };
 
         static REFERENCE_ID: u8 = 0;
         static KEY_LEN: usize = ID_LEN + HASH_LEN;
 
+        // Retrieval
+
+        pub fn get(read: &Read<'_>, hash: u64) -> Result<Option<Identifier>, Box<dyn Error>> {
+            let mut key = [0u8; KEY_LEN];
+
+            write_key(&mut key, hash);
+
+            let value = read.partitions.reference.get(key)?;
+
+            Ok(value
+                .map(|value| String::from_utf8(value.to_vec()))
+                .transpose()?
+                .map(Identifier::from))
+        }
+
+        pub fn all(read: &Read<'_>, prefix: &str) -> Result<Vec<Identifier>, Box<dyn Error>> {
+            let mut values = Vec::new();
+
+            for key_value in read.partitions.reference.prefix([REFERENCE_ID]) {
+                let (_, value) = key_value?;
+
+                if value.starts_with(prefix.as_bytes()) {
+                    values.push(Identifier::from(String::from_utf8(value.to_vec())?));
+                }
+            }
+
+            values.sort();
+
+            Ok(values)
+        }
+
+        pub fn len(read: &Read<'_>) -> Result<u64, Box<dyn Error>> {
+            let len = read
+                .partitions
+                .reference
+                .prefix([REFERENCE_ID])
+                .try_fold(0, |len, key_value| key_value.map(|_| len + 1))?;
+
+            Ok(len)
+        }
+
+        // Backup
+
+        pub fn retains(key: &[u8], hashes: &BTreeSet<u64>) -> Option<bool> {
+            let mut key = key.strip_prefix(&[REFERENCE_ID])?;
+
+            (key.len() == HASH_LEN).then(|| hashes.contains(&key.get_u64()))
+        }
+
         // Insertion
 
         pub fn insert(write: &mut Write<'_>, descriptor: &HashedDescriptor) {
             let mut key = [0u8; KEY_LEN];
 
-            write_key(&mut key, descriptor);
+            write_key(&mut key, descriptor.identifer().hash());
 
             let value = descriptor.identifer().value().as_bytes();
 
@@ -82,11 +337,10 @@ mod descriptor {
 
         // Keys/Prefixes
 
-        fn write_key(key: &mut [u8; KEY_LEN], descriptor: &HashedDescriptor) {
+        fn write_key(key: &mut [u8; KEY_LEN], descriptor_identifier: u64) {
             let mut key = &mut key[..];
 
             let reference_id = REFERENCE_ID;
-            let descriptor_identifier = descriptor.identifer().hash();
 
             key.put_u8(reference_id);
             key.put_u64(descriptor_identifier);
@@ -99,60 +353,664 @@ mod descriptor {
 // Tags
 
 mod tags {
-    use crate::persistence::{
-        HashedTag,
-        Write,
+    use std::{
+        collections::BTreeSet,
+        error::Error,
+    };
+
+    use crate::{
+        model::Tag,
+        persistence::{
+            HashedTag,
+            Read,
+            Write,
+        },
     };
 
     static HASH_LEN: usize = size_of::<u64>();
 
+    // Retrieval
+
+    pub fn get(read: &Read<'_>, hash: u64) -> Result<Option<Tag>, Box<dyn Error>> {
+        lookup::get(read, hash)
+    }
+
+    pub fn all(read: &Read<'_>, prefix: &str) -> Result<Vec<Tag>, Box<dyn Error>> {
+        lookup::all(read, prefix)
+    }
+
+    pub fn len(read: &Read<'_>) -> Result<u64, Box<dyn Error>> {
+        lookup::len(read)
+    }
+
+    // Backup
+
+    pub fn retains(key: &[u8], tags: &BTreeSet<u64>) -> Option<bool> {
+        lookup::retains(key, tags)
+    }
+
     // Insertion
 
     pub fn insert(write: &mut Write<'_>, tags: &[HashedTag]) {
         lookup::insert(write, tags);
     }
 
+    // Removal
+
+    pub fn remove(write: &mut Write<'_>, tags: &[u64]) {
+        lookup::remove(write, tags);
+    }
+
     // Lookup Reference
 
     mod lookup {
-        use bytes::BufMut as _;
+        use std::{
+            collections::BTreeSet,
+            error::Error,
+        };
 
-        use crate::persistence::{
-            HashedTag,
-            Write,
-            reference::{
-                ID_LEN,
-                tags::HASH_LEN,
+        use bytes::{
+            Buf as _,
+            BufMut as _,
+        };
+
+        use crate::{
+            model::Tag,
+            persistence::{
+                HashedTag,
+                Read,
+                Write,
+                reference::{
+                    ID_LEN,
+                    tags::HASH_LEN,
+                },
             },
         };
 
         static REFERENCE_ID: u8 = 1;
         static KEY_LEN: usize = ID_LEN + HASH_LEN;
 
+        // Retrieval
+
+        pub fn get(read: &Read<'_>, hash: u64) -> Result<Option<Tag>, Box<dyn Error>> {
+            let mut key = [0u8; KEY_LEN];
+
+            write_key(&mut key, hash);
+
+            let value = read.partitions.reference.get(key)?;
+
+            Ok(value
+                .map(|value| String::from_utf8(value.to_vec()))
+                .transpose()?
+                .map(Tag::decode))
+        }
+
+        pub fn all(read: &Read<'_>, prefix: &str) -> Result<Vec<Tag>, Box<dyn Error>> {
+            let mut values = Vec::new();
+
+            for key_value in read.partitions.reference.prefix([REFERENCE_ID]) {
+                let (_, value) = key_value?;
+                let tag = Tag::decode(String::from_utf8(value.to_vec())?);
+
+                if tag.value().starts_with(prefix) {
+                    values.push(tag);
+                }
+            }
+
+            values.sort();
+
+            Ok(values)
+        }
+
+        pub fn len(read: &Read<'_>) -> Result<u64, Box<dyn Error>> {
+            let len = read
+                .partitions
+                .reference
+                .prefix([REFERENCE_ID])
+                .try_fold(0, |len, key_value| key_value.map(|_| len + 1))?;
+
+            Ok(len)
+        }
+
+        // Backup
+
+        pub fn retains(key: &[u8], hashes: &BTreeSet<u64>) -> Option<bool> {
+            let mut key = key.strip_prefix(&[REFERENCE_ID])?;
+
+            (key.len() == HASH_LEN).then(|| hashes.contains(&key.get_u64()))
+        }
+
         // Insertion
 
         pub fn insert(write: &mut Write<'_>, tags: &[HashedTag]) {
             let mut key = [0u8; KEY_LEN];
 
             for tag in tags {
-                write_key(&mut key, tag);
+                write_key(&mut key, tag.hash());
+
+                let value = tag.encode();
+
+                write
+                    .batch
+                    .insert(&write.partitions.reference, key, value.as_bytes());
+            }
+        }
 
-                let value = tag.value().as_bytes();
+        // Removal
 
-                write.batch.insert(&write.partitions.reference, key, value);
+        pub fn remove(write: &mut Write<'_>, tags: &[u64]) {
+            let mut key = [0u8; KEY_LEN];
+
+            for tag in tags {
+                write_key(&mut key, *tag);
+
+                write.batch.remove(&write.partitions.reference, key);
             }
         }
 
         // Keys/Prefixes
 
-        fn write_key(key: &mut [u8; KEY_LEN], tag: &HashedTag) {
+        fn write_key(key: &mut [u8; KEY_LEN], tag: u64) {
             let mut key = &mut key[..];
 
             let reference_id = REFERENCE_ID;
-            let tag = tag.hash();
 
             key.put_u8(reference_id);
             key.put_u64(tag);
         }
     }
 }
+
+// -------------------------------------------------------------------------------------------------
+
+// Dictionary
+
+mod dictionary {
+    use std::{
+        error::Error,
+        iter,
+    };
+
+    use bytes::{
+        Buf as _,
+        BufMut as _,
+    };
+
+    use crate::{
+        model::{
+            Identifier,
+            Tag,
+        },
+        persistence::{
+            Database,
+            HashedEvent,
+            HashedQuery,
+            Partitions,
+            Read,
+            Write,
+            reference::{
+                Assigned,
+                ID_LEN,
+                Referenced,
+                tags,
+            },
+        },
+    };
+
+    static BACKFILL_BATCH_LEN: usize = 1024;
+    static IDENTIFIER_KIND: u8 = 0;
+    static SEQUENCE_ID: u8 = 3;
+    static TAG_KIND: u8 = 1;
+    static TAG_KEY_KIND: u8 = 2;
+    static UNASSIGNED: u64 = u64::MAX;
+    static VALUE_ID: u8 = 2;
+
+    // Retrieval
+
+    pub fn tag_key(read: &Read<'_>, key: &str) -> Result<Option<u64>, Box<dyn Error>> {
+        find(read, None, TAG_KEY_KIND, key)
+    }
+
+    pub fn identifier_names(
+        read: &Read<'_>,
+        prefix: &str,
+    ) -> Result<Vec<Identifier>, Box<dyn Error>> {
+        names(read, IDENTIFIER_KIND, prefix)
+            .map(|names| names.into_iter().map(Identifier::from).collect())
+    }
+
+    pub fn tag_names(read: &Read<'_>, prefix: &str) -> Result<Vec<Tag>, Box<dyn Error>> {
+        let keyed = prefix.match_indices(':').map(|(index, _)| {
+            let (key, value) = prefix.split_at(index);
+
+            Tag::keyed(key, &value[1..]).encode().into_owned()
+        });
+
+        let mut tags = Vec::new();
+
+        for prefix in iter::once(prefix.to_owned()).chain(keyed) {
+            tags.extend(names(read, TAG_KIND, &prefix)?.into_iter().map(Tag::decode));
+        }
+
+        tags.sort();
+
+        Ok(tags)
+    }
+
+    fn names(read: &Read<'_>, kind: u8, prefix: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        read.partitions
+            .reference
+            .prefix(write_key(kind, prefix))
+            .map(|key_value| {
+                let (key, _) = key_value?;
+
+                Ok(String::from_utf8(key[ID_LEN * 2..].to_vec())?)
+            })
+            .collect()
+    }
+
+    // Backup
+
+    pub fn retains(key: &[u8], value: &[u8], referenced: &Referenced) -> Option<bool> {
+        let (kind, name) = key.strip_prefix(&[VALUE_ID])?.split_first()?;
+        let mut value = value;
+
+        match *kind {
+            kind if kind == IDENTIFIER_KIND => {
+                Some(referenced.identifiers.contains(&value.get_u64()))
+            }
+            kind if kind == TAG_KIND => Some(referenced.tags.contains(&value.get_u64())),
+            kind if kind == TAG_KEY_KIND => {
+                Some(str::from_utf8(name).is_ok_and(|name| referenced.tag_keys.contains(name)))
+            }
+            _ => None,
+        }
+    }
+
+    // Insertion
+
+    pub fn insert(write: &mut Write<'_>, event: &HashedEvent) {
+        let reference = &write.partitions.reference;
+        let identifier = event.descriptor.identifer();
+
+        write.batch.insert(
+            reference,
+            write_key(IDENTIFIER_KIND, identifier.value()),
+            identifier.hash().to_be_bytes(),
+        );
+
+        for tag in &event.tags {
+            write.batch.insert(
+                reference,
+                write_key(TAG_KIND, &tag.encode()),
+                tag.hash().to_be_bytes(),
+            );
+        }
+    }
+
+    // Assignment
+
+    pub fn assign(write: &mut Write<'_>, event: &mut HashedEvent) -> Result<(), Box<dyn Error>> {
+        let identifier = &mut event.descriptor.0;
+
+        identifier.0 = allocate(write, IDENTIFIER_KIND, identifier.1.value())?;
+
+        for tag in &mut event.tags {
+            tag.0 = allocate(write, TAG_KIND, &tag.2.encode())?;
+            tag.1 = tag
+                .2
+                .key()
+                .map(|key| allocate(write, TAG_KEY_KIND, key))
+                .transpose()?;
+        }
+
+        Ok(())
+    }
+
+    // Resolution
+
+    pub fn resolve(read: &Read<'_>, event: &mut HashedEvent) -> Result<(), Box<dyn Error>> {
+        let identifier = &mut event.descriptor.0;
+
+        identifier.0 = find(read, None, IDENTIFIER_KIND, identifier.1.value())?
+            .ok_or("missing identifier reference")?;
+
+        for tag in &mut event.tags {
+            tag.0 = find(read, None, TAG_KIND, &tag.2.encode())?.ok_or("missing tag reference")?;
+            tag.1 = match tag.2.key() {
+                Some(key) => {
+                    Some(find(read, None, TAG_KEY_KIND, key)?.ok_or("missing tag key reference")?)
+                }
+                None => None,
+            };
+        }
+
+        Ok(())
+    }
+
+    pub fn resolve_query(
+        read: &Read<'_>,
+        assigned: Option<&Assigned>,
+        query: &mut HashedQuery,
+    ) -> Result<(), Box<dyn Error>> {
+        let lookup = |kind, value: &str| {
+            find(read, assigned, kind, value).map(|id| id.unwrap_or(UNASSIGNED))
+        };
+
+        for selector in &mut query.0 {
+            for specifier in &mut selector.0 {
+                let identifier = &mut specifier.0;
+
+                identifier.0 = lookup(IDENTIFIER_KIND, identifier.1.value())?;
+            }
+
+            for tag in &mut selector.1 {
+                tag.0 = lookup(TAG_KIND, &tag.2.encode())?;
+            }
+
+            for tag_key in &mut selector.3 {
+                tag_key.0 = lookup(TAG_KEY_KIND, tag_key.key())?;
+
+                for tag in &mut tag_key.1 {
+                    tag.0 = lookup(TAG_KIND, &tag.2.encode())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Removal
+
+    pub fn remove_tags(write: &mut Write<'_>, tags: &[u64]) -> Result<(), Box<dyn Error>> {
+        let read = Read::new(write.partitions);
+
+        for tag in tags {
+            if let Some(tag) = tags::get(&read, *tag)? {
+                write
+                    .assigned
+                    .ids
+                    .remove(&(TAG_KIND, tag.encode().into_owned()));
+                write.batch.remove(
+                    &write.partitions.reference,
+                    write_key(TAG_KIND, &tag.encode()),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    // Maintenance
+
+    pub fn backfill(database: &Database, partitions: &Partitions) -> Result<(), Box<dyn Error>> {
+        let mut batch = database.batch();
+
+        for kind in [IDENTIFIER_KIND, TAG_KIND] {
+            for key_value in partitions.reference.prefix([kind]) {
+                let (key, value) = key_value?;
+
+                let mut key = &key[ID_LEN..];
+                let id = key.get_u64();
+                let value = String::from_utf8(value.to_vec())?;
+
+                batch.insert(
+                    &partitions.reference,
+                    write_key(kind, &value),
+                    id.to_be_bytes(),
+                );
+
+                if batch.len() >= BACKFILL_BATCH_LEN {
+                    batch.commit()?;
+                    batch = database.batch();
+                }
+            }
+        }
+
+        batch.commit()?;
+
+        Ok(())
+    }
+
+    // Lookup
+
+    fn find(
+        read: &Read<'_>,
+        assigned: Option<&Assigned>,
+        kind: u8,
+        value: &str,
+    ) -> Result<Option<u64>, Box<dyn Error>> {
+        if let Some(id) = assigned.and_then(|assigned| assigned.ids.get(&(kind, value.to_owned())))
+        {
+            return Ok(Some(*id));
+        }
+
+        let id = read.partitions.reference.get(write_key(kind, value))?;
+
+        Ok(id.map(|id| id.as_ref().get_u64()))
+    }
+
+    fn allocate(write: &mut Write<'_>, kind: u8, value: &str) -> Result<u64, Box<dyn Error>> {
+        let read = Read::new(write.partitions);
+
+        if let Some(id) = find(&read, Some(&write.assigned), kind, value)? {
+            return Ok(id);
+        }
+
+        let id = match write.assigned.sequences.get(&kind) {
+            Some(id) => *id,
+            None => sequence(&read, kind)?,
+        };
+
+        let reference = &write.partitions.reference;
+
+        write
+            .batch
+            .insert(reference, write_key(kind, value), id.to_be_bytes());
+        write
+            .batch
+            .insert(reference, [SEQUENCE_ID, kind], (id + 1).to_be_bytes());
+
+        write.assigned.ids.insert((kind, value.to_owned()), id);
+        write.assigned.sequences.insert(kind, id + 1);
+
+        Ok(id)
+    }
+
+    fn sequence(read: &Read<'_>, kind: u8) -> Result<u64, Box<dyn Error>> {
+        let value = read.partitions.reference.get([SEQUENCE_ID, kind])?;
+
+        Ok(value.map_or(0, |value| value.as_ref().get_u64()))
+    }
+
+    // Keys/Prefixes
+
+    fn write_key(kind: u8, value: &str) -> Vec<u8> {
+        let mut key = Vec::with_capacity(ID_LEN * 2 + value.len());
+
+        key.put_u8(VALUE_ID);
+        key.put_u8(kind);
+        key.put_slice(value.as_bytes());
+
+        key
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::{
+        model::{
+            Event,
+            Identifier,
+            Query,
+            Selector,
+            Specifier,
+            Stream,
+            Tag,
+            TagKey,
+        },
+        options::{
+            Encoding,
+            StreamOptions,
+        },
+        persistence::{
+            self,
+            Database,
+            Read,
+            backend::MemoryBackend,
+            reference,
+        },
+    };
+
+    fn events(stream: &Stream, selector: Selector) -> Result<Vec<(u64, String)>, Box<dyn Error>> {
+        stream
+            .query(Query::from([selector]), None)
+            .map(|event| {
+                event.map(|event| {
+                    let identifier = event.event.descriptor.identifier().value().to_owned();
+
+                    (event.position.value(), identifier)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn event_types_and_tags_are_listed_by_prefix() -> Result<(), Box<dyn Error>> {
+        let database = Database::memory(StreamOptions::default());
+        let mut stream = database.stream("orders")?;
+
+        stream.append([
+            Event::new(b"placed".to_vec(), ("order-placed", 0), [
+                Tag::from("order:1"),
+                Tag::keyed("tenant", "a"),
+            ]),
+            Event::new(b"paid".to_vec(), ("order-paid", 0), [
+                Tag::from("order:1"),
+                Tag::keyed("tenant", "b"),
+                Tag::from("vip"),
+            ]),
+            Event::new(b"sent".to_vec(), ("invoice-sent", 0), [Tag::from(
+                "tenant:c",
+            )]),
+        ])?;
+
+        assert_eq!(stream.event_types()?, [
+            Identifier::from("invoice-sent"),
+            Identifier::from("order-paid"),
+            Identifier::from("order-placed"),
+        ]);
+        assert_eq!(stream.tags("tenant:")?, [
+            Tag::keyed("tenant", "a"),
+            Tag::keyed("tenant", "b"),
+            Tag::from("tenant:c"),
+        ]);
+        assert_eq!(stream.tags("tenant:a")?, [Tag::keyed("tenant", "a")]);
+        assert_eq!(stream.tags("")?.len(), 5);
+        assert_eq!(stream.tags("missing")?, Vec::<Tag>::new());
+
+        let mut partitions = persistence::partitions(&database, Some("orders"))?;
+
+        partitions.indexed.names = false;
+
+        let read = Read::new(&partitions);
+
+        assert_eq!(reference::identifiers(&read, "order-")?, [
+            Identifier::from("order-paid"),
+            Identifier::from("order-placed"),
+        ]);
+        assert_eq!(reference::tags(&read, "order:")?, [Tag::from("order:1")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dictionary_ids_resolve_after_reopen() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+        let options = StreamOptions::default().encoding(Encoding::Dictionary);
+        let mut stream = Stream::with_backend(backend.clone(), options)?;
+
+        stream.append([
+            Event::new(b"placed".to_vec(), ("order-placed", 0), [Tag::keyed(
+                "student", "1",
+            )]),
+            Event::new(b"enrolled".to_vec(), ("enrolled", 0), [
+                Tag::keyed("student", "1"),
+                Tag::keyed("course", "7"),
+            ]),
+        ])?;
+
+        drop(stream);
+
+        let mut stream = Stream::with_backend(backend.clone(), StreamOptions::default())?;
+
+        stream.append([Event::new(b"enrolled".to_vec(), ("enrolled", 0), [
+            Tag::keyed("student", "2"),
+            Tag::keyed("course", "7"),
+        ])])?;
+
+        let by_tag = Selector::from((
+            Vec::<Specifier>::new(),
+            [Tag::keyed("course", "7")],
+            Vec::<String>::new(),
+            Vec::<TagKey>::new(),
+        ));
+        let by_type = Selector::from((
+            [Specifier::from(("order-placed", None))],
+            Vec::<Tag>::new(),
+            Vec::<String>::new(),
+            Vec::<TagKey>::new(),
+        ));
+        let by_key = Selector::from((
+            Vec::<Specifier>::new(),
+            Vec::<Tag>::new(),
+            Vec::<String>::new(),
+            [TagKey::with_values("student", ["2"])],
+        ));
+
+        assert_eq!(events(&stream, by_tag)?, [
+            (1, "enrolled".to_owned()),
+            (2, "enrolled".to_owned()),
+        ]);
+        assert_eq!(events(&stream, by_type)?, [(0, "order-placed".to_owned())]);
+        assert_eq!(events(&stream, by_key)?, [(2, "enrolled".to_owned())]);
+
+        let event = stream
+            .query(Query::all(), None)
+            .next()
+            .ok_or("missing event")??;
+
+        assert_eq!(event.event.tags, [Tag::keyed("student", "1")]);
+
+        let database = Database::with_backend(backend, StreamOptions::default());
+        let partitions = persistence::partitions(&database, None)?;
+        let read = Read::new(&partitions);
+
+        assert_eq!(reference::tag_key(&read, "student")?, 0);
+        assert_eq!(reference::tag_key(&read, "course")?, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dictionary_encoding_requires_empty_stream() -> Result<(), Box<dyn Error>> {
+        let backend = MemoryBackend::default();
+        let mut stream = Stream::with_backend(backend.clone(), StreamOptions::default())?;
+
+        stream.append([Event::new(b"placed".to_vec(), ("order-placed", 0), [])])?;
+
+        drop(stream);
+
+        let options = StreamOptions::default().encoding(Encoding::Dictionary);
+
+        assert!(Stream::with_backend(backend, options).is_err());
+
+        Ok(())
+    }
+}
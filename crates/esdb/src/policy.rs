@@ -0,0 +1,647 @@
+use std::{
+    error::Error,
+    iter,
+    path::Path,
+};
+
+use derive_more::{
+    Debug,
+    Display,
+    Error,
+};
+use fancy_constructor::new;
+
+use crate::{
+    archive::Archive,
+    model::{
+        AuditEntry,
+        Condition,
+        Event,
+        Identifier,
+        Position,
+        Query,
+        Redaction,
+        Selector,
+        SequencedEvent,
+        Stats,
+        Stream,
+        Tag,
+        TagKey,
+    },
+    options::Retention,
+};
+
+// =================================================================================================
+// Policy
+// =================================================================================================
+
+// Policy Stream
+
+#[derive(new, Debug)]
+#[new(vis())]
+pub struct PolicyStream {
+    stream: Stream,
+}
+
+impl From<Stream> for PolicyStream {
+    fn from(stream: Stream) -> Self {
+        Self::new(stream)
+    }
+}
+
+impl PolicyStream {
+    pub fn into_inner(self) -> Stream {
+        self.stream
+    }
+}
+
+impl PolicyStream {
+    pub fn append<E>(&mut self, principal: &Principal, events: E) -> Result<(), Box<dyn Error>>
+    where
+        E: IntoIterator<Item = Event>,
+    {
+        let events = authorize(principal, events)?;
+
+        self.stream.append(events)
+    }
+
+    pub fn append_if<E, C>(
+        &mut self,
+        principal: &Principal,
+        events: E,
+        condition: C,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        E: IntoIterator<Item = Event>,
+        C: Into<Condition>,
+    {
+        let events = authorize(principal, events)?;
+        let condition = inspect(principal, condition.into())?;
+
+        self.stream.append_if(events, condition)
+    }
+}
+
+impl PolicyStream {
+    pub fn query<'a, Q>(
+        &'a self,
+        principal: &'a Principal,
+        query: Q,
+        position: Option<Position>,
+    ) -> Box<dyn Iterator<Item = Result<SequencedEvent, Box<dyn Error>>> + 'a>
+    where
+        Q: Into<Query>,
+    {
+        if !principal.can(Access::Read) {
+            return Box::new(iter::once(Err(denied(principal, Access::Read))));
+        }
+
+        let query = query.into();
+        let events = self.stream.query(query, position).filter(|event| {
+            event
+                .as_ref()
+                .map_or(true, |event| principal.permits(Access::Read, &event.event))
+        });
+
+        Box::new(events)
+    }
+
+    pub fn event_types(&self, principal: &Principal) -> Result<Vec<Identifier>, Box<dyn Error>> {
+        let identifiers = self.stream.event_types()?;
+
+        Ok(identifiers
+            .into_iter()
+            .filter(|identifier| principal.permits_identifier(Access::Read, identifier))
+            .collect())
+    }
+
+    pub fn tags(&self, principal: &Principal, prefix: &str) -> Result<Vec<Tag>, Box<dyn Error>> {
+        let tags = self.stream.tags(prefix)?;
+
+        Ok(tags
+            .into_iter()
+            .filter(|tag| principal.permits_tag(Access::Read, tag))
+            .collect())
+    }
+}
+
+impl PolicyStream {
+    pub fn archive<A>(
+        &mut self,
+        principal: &Principal,
+        retention: &Retention,
+        archive: &mut A,
+    ) -> Result<u64, Box<dyn Error>>
+    where
+        A: Archive,
+    {
+        administer(principal)?;
+
+        self.stream.archive(retention, archive)
+    }
+
+    pub fn audit(&self, principal: &Principal) -> Result<Vec<AuditEntry>, Box<dyn Error>> {
+        administer(principal)?;
+
+        self.stream.audit()
+    }
+
+    pub fn backup<P>(&self, principal: &Principal, path: P) -> Result<(), Box<dyn Error>>
+    where
+        P: AsRef<Path>,
+    {
+        administer(principal)?;
+
+        self.stream.backup(path)
+    }
+
+    pub fn redact(
+        &mut self,
+        principal: &Principal,
+        position: Position,
+        redaction: &Redaction,
+    ) -> Result<(), Box<dyn Error>> {
+        let event = self
+            .stream
+            .query(Query::all(), Some(position))
+            .next()
+            .transpose()?
+            .filter(|event| event.position == position);
+
+        match event {
+            Some(event) if principal.permits(Access::Admin, &event.event) => {}
+            Some(_) => return Err(denied(principal, Access::Admin)),
+            None => administer(principal)?,
+        }
+
+        self.stream.redact(position, redaction)
+    }
+
+    pub fn stats(&self, principal: &Principal) -> Result<Stats, Box<dyn Error>> {
+        administer(principal)?;
+
+        self.stream.stats()
+    }
+}
+
+fn authorize<E>(principal: &Principal, events: E) -> Result<Vec<Event>, Box<dyn Error>>
+where
+    E: IntoIterator<Item = Event>,
+{
+    let events = events.into_iter().collect::<Vec<_>>();
+
+    if events
+        .iter()
+        .all(|event| principal.permits(Access::Append, event))
+    {
+        Ok(events)
+    } else {
+        Err(denied(principal, Access::Append))
+    }
+}
+
+fn inspect(principal: &Principal, condition: Condition) -> Result<Condition, Box<dyn Error>> {
+    if condition
+        .query()
+        .selectors()
+        .iter()
+        .all(|selector| principal.permits_selector(Access::Read, selector))
+    {
+        Ok(condition)
+    } else {
+        Err(denied(principal, Access::Read))
+    }
+}
+
+fn administer(principal: &Principal) -> Result<(), Box<dyn Error>> {
+    if principal.can_unscoped(Access::Admin) {
+        Ok(())
+    } else {
+        Err(denied(principal, Access::Admin))
+    }
+}
+
+fn denied(principal: &Principal, access: Access) -> Box<dyn Error> {
+    Denied::new(principal.name.clone(), access).into()
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Principal
+
+#[derive(new, Clone, Debug)]
+#[must_use]
+pub struct Principal {
+    #[new(into)]
+    name: String,
+    #[new(default)]
+    rules: Vec<Rule>,
+}
+
+impl Principal {
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn permits(&self, access: Access, event: &Event) -> bool {
+        self.rules.iter().any(|rule| rule.permits(access, event))
+    }
+
+    fn permits_identifier(&self, access: Access, identifier: &Identifier) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.grants(access) && rule.matches_identifier(identifier))
+    }
+
+    fn permits_tag(&self, access: Access, tag: &Tag) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.grants(access) && rule.matches_tag(tag))
+    }
+
+    fn permits_selector(&self, access: Access, selector: &Selector) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.grants(access) && rule.covers(selector))
+    }
+
+    fn can(&self, access: Access) -> bool {
+        self.rules.iter().any(|rule| rule.grants(access))
+    }
+
+    fn can_unscoped(&self, access: Access) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.grants(access) && rule.is_unscoped())
+    }
+}
+
+// Rule
+
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct Rule {
+    access: Vec<Access>,
+    identifiers: Vec<String>,
+    tags: Vec<String>,
+}
+
+impl Rule {
+    pub fn new<A>(access: A) -> Self
+    where
+        A: IntoIterator<Item = Access>,
+    {
+        Self {
+            access: access.into_iter().collect(),
+            identifiers: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn identifier<P>(mut self, pattern: P) -> Self
+    where
+        P: Into<String>,
+    {
+        self.identifiers.push(pattern.into());
+        self
+    }
+
+    pub fn tag<P>(mut self, pattern: P) -> Self
+    where
+        P: Into<String>,
+    {
+        self.tags.push(pattern.into());
+        self
+    }
+
+    pub fn permits(&self, access: Access, event: &Event) -> bool {
+        self.grants(access)
+            && self.matches_identifier(event.descriptor.identifier())
+            && self.covers_event_tags(&event.tags)
+    }
+
+    fn grants(&self, access: Access) -> bool {
+        self.access.contains(&access)
+    }
+
+    fn is_unscoped(&self) -> bool {
+        self.identifiers.is_empty() && self.tags.is_empty()
+    }
+
+    fn covers(&self, selector: &Selector) -> bool {
+        self.covers_identifiers(selector) && self.covers_tags(selector)
+    }
+
+    fn covers_identifiers(&self, selector: &Selector) -> bool {
+        let specifiers = selector.specifiers();
+
+        self.identifiers.is_empty()
+            || (!specifiers.is_empty()
+                && specifiers
+                    .iter()
+                    .all(|specifier| self.matches_identifier(specifier.identifier())))
+    }
+
+    fn covers_event_tags(&self, tags: &[Tag]) -> bool {
+        self.tags.is_empty() || (!tags.is_empty() && tags.iter().all(|tag| self.matches_tag(tag)))
+    }
+
+    fn covers_tags(&self, selector: &Selector) -> bool {
+        if self.tags.is_empty() {
+            return true;
+        }
+
+        let tags = selector
+            .tags()
+            .iter()
+            .cloned()
+            .chain(selector.tag_keys().iter().flat_map(TagKey::tags))
+            .collect::<Vec<_>>();
+
+        let prefixes = selector
+            .tag_prefixes()
+            .iter()
+            .cloned()
+            .chain(
+                selector
+                    .tag_keys()
+                    .iter()
+                    .filter(|tag_key| tag_key.values().is_empty())
+                    .map(|tag_key| format!("{}:", tag_key.key())),
+            )
+            .collect::<Vec<_>>();
+
+        (!tags.is_empty() || !prefixes.is_empty())
+            && tags.iter().all(|tag| self.matches_tag(tag))
+            && prefixes.iter().all(|prefix| self.matches_prefix(prefix))
+    }
+
+    fn matches_identifier(&self, identifier: &Identifier) -> bool {
+        self.identifiers.is_empty()
+            || self
+                .identifiers
+                .iter()
+                .any(|pattern| matches(pattern, identifier.value()))
+    }
+
+    fn matches_tag(&self, tag: &Tag) -> bool {
+        self.tags.is_empty()
+            || self
+                .tags
+                .iter()
+                .any(|pattern| matches(pattern, tag.value()))
+    }
+
+    fn matches_prefix(&self, prefix: &str) -> bool {
+        self.tags.iter().any(|pattern| {
+            pattern
+                .strip_suffix('*')
+                .is_some_and(|stem| prefix.starts_with(stem))
+        })
+    }
+}
+
+fn matches(pattern: &str, value: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => value.starts_with(prefix),
+        None => value == pattern,
+    }
+}
+
+// Access
+
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+pub enum Access {
+    #[display("read")]
+    Read,
+    #[display("append")]
+    Append,
+    #[display("administer")]
+    Admin,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Errors
+
+#[derive(new, Debug, Display, Error)]
+#[display("principal {principal} is not permitted to {access}")]
+#[new(vis())]
+pub struct Denied {
+    principal: String,
+    access: Access,
+}
+
+impl Denied {
+    pub fn principal(&self) -> &str {
+        &self.principal
+    }
+
+    pub fn access(&self) -> Access {
+        self.access
+    }
+}
+
+// =================================================================================================
+// Tests
+// =================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::{
+        model::{
+            Event,
+            Position,
+            Query,
+            Redaction,
+            Selector,
+            Specifier,
+            Stream,
+            Tag,
+            TagKey,
+        },
+        options::StreamOptions,
+        policy::{
+            Access,
+            Denied,
+            PolicyStream,
+            Principal,
+            Rule,
+            inspect,
+        },
+    };
+
+    fn event<T>(tags: T) -> Event
+    where
+        T: Into<Vec<Tag>>,
+    {
+        Event::new(b"data".to_vec(), ("order-placed", 0), tags)
+    }
+
+    fn selector<T, P, K>(tags: T, prefixes: P, tag_keys: K) -> Selector
+    where
+        T: Into<Vec<Tag>>,
+        P: Into<Vec<String>>,
+        K: Into<Vec<TagKey>>,
+    {
+        Selector::from((Vec::<Specifier>::new(), tags, prefixes, tag_keys))
+    }
+
+    fn scoped(access: Access) -> Principal {
+        Principal::new("scoped").rule(Rule::new([access]).tag("tenant:a*"))
+    }
+
+    fn unscoped(access: Access) -> Principal {
+        Principal::new("unscoped").rule(Rule::new([access]))
+    }
+
+    fn is_denied(result: Result<(), Box<dyn Error>>, access: Access) -> bool {
+        result.is_err_and(|err| {
+            err.downcast_ref::<Denied>()
+                .is_some_and(|denied| denied.access() == access)
+        })
+    }
+
+    #[test]
+    fn tagged_rules_deny_untagged_events() {
+        let principal = scoped(Access::Read);
+
+        assert!(!principal.permits(Access::Read, &event([])));
+        assert!(!principal.permits(Access::Read, &event([Tag::from("tenant:b")])));
+        assert!(!principal.permits(
+            Access::Read,
+            &event([Tag::from("tenant:a"), Tag::from("tenant:b")])
+        ));
+        assert!(principal.permits(Access::Read, &event([Tag::from("tenant:a")])));
+        assert!(unscoped(Access::Read).permits(Access::Read, &event([])));
+    }
+
+    #[test]
+    fn tagged_rules_cover_matching_selectors() {
+        let rule = Rule::new([Access::Read]).tag("tenant:a*");
+
+        assert!(rule.covers_tags(&selector([Tag::from("tenant:a1")], [], [])));
+        assert!(rule.covers_tags(&selector([], [String::from("tenant:a")], [])));
+        assert!(
+            rule.covers_tags(&selector([], [], [TagKey::with_values("tenant", [
+                "a1", "a2"
+            ])]))
+        );
+
+        assert!(!rule.covers_tags(&selector([], [], [])));
+        assert!(!rule.covers_tags(&selector([Tag::from("tenant:b")], [], [])));
+        assert!(!rule.covers_tags(&selector([], [String::from("tenant:")], [])));
+        assert!(!rule.covers_tags(&selector([], [], [TagKey::from("tenant")])));
+    }
+
+    #[test]
+    fn conditions_require_read_access() {
+        let covered = Query::from([selector([Tag::from("tenant:a")], [], [])]);
+        let uncovered = Query::from([selector([Tag::from("tenant:b")], [], [])]);
+
+        assert!(inspect(&scoped(Access::Read), (covered.clone(), None).into()).is_ok());
+        assert!(inspect(&scoped(Access::Read), (uncovered, None).into()).is_err());
+        assert!(inspect(&scoped(Access::Append), (covered, None).into()).is_err());
+    }
+
+    #[test]
+    fn policy_streams_scope_appends_and_queries() -> Result<(), Box<dyn Error>> {
+        let mut stream = PolicyStream::from(Stream::memory(StreamOptions::default())?);
+        let writer = Principal::new("writer")
+            .rule(Rule::new([Access::Append, Access::Read]).tag("tenant:a*"));
+
+        stream.append(&writer, [event([Tag::from("tenant:a")])])?;
+
+        assert!(is_denied(
+            stream.append(&writer, [event([Tag::from("tenant:b")])]),
+            Access::Append
+        ));
+        assert!(is_denied(
+            stream.append(&writer, [event([])]),
+            Access::Append
+        ));
+
+        let appended = stream.into_inner().query(Query::all(), None).count();
+
+        assert_eq!(appended, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn policy_streams_filter_reads() -> Result<(), Box<dyn Error>> {
+        let mut inner = Stream::memory(StreamOptions::default())?;
+
+        inner.append([
+            event([Tag::from("tenant:a")]),
+            event([Tag::from("tenant:b")]),
+            event([]),
+        ])?;
+
+        let stream = PolicyStream::from(inner);
+        let reader = scoped(Access::Read);
+
+        let positions = stream
+            .query(&reader, Query::all(), None)
+            .map(|event| event.map(|event| event.position.value()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(positions, [0]);
+
+        let tags = stream.tags(&reader, "tenant:")?;
+
+        assert_eq!(tags, [Tag::from("tenant:a")]);
+
+        let denied = stream
+            .query(&scoped(Access::Append), Query::all(), None)
+            .collect::<Result<Vec<_>, _>>();
+
+        assert!(denied.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn scoped_admins_redact_covered_events() -> Result<(), Box<dyn Error>> {
+        let mut inner = Stream::memory(StreamOptions::default())?;
+
+        inner.append([
+            event([Tag::from("tenant:a")]),
+            event([Tag::from("tenant:b")]),
+        ])?;
+
+        let mut stream = PolicyStream::from(inner);
+        let admin = scoped(Access::Admin);
+        let redaction = Redaction::new(Vec::new(), "admin", "erasure request");
+
+        stream.redact(&admin, Position::from(0u64), &redaction)?;
+
+        assert!(is_denied(
+            stream.redact(&admin, Position::from(1u64), &redaction),
+            Access::Admin
+        ));
+        assert!(is_denied(
+            stream.redact(&admin, Position::from(2u64), &redaction),
+            Access::Admin
+        ));
+        assert!(stream.audit(&admin).is_err());
+
+        let audit = stream.audit(&unscoped(Access::Admin))?;
+
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].position(), Position::from(0u64));
+
+        Ok(())
+    }
+}
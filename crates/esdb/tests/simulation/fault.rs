@@ -0,0 +1,265 @@
+use std::{
+    error::Error,
+    iter,
+    mem,
+    ops::Bound,
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
+};
+
+use esdb::{
+    Backend,
+    Durability,
+    MemoryBackend,
+    Operation,
+    Partition,
+    PartitionOptions,
+    persistence::backend::{
+        KeyValues,
+        KvPair,
+        Slice,
+    },
+};
+use fastrand::Rng;
+
+// =================================================================================================
+// Fault
+// =================================================================================================
+
+// Faults
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Faults {
+    pub crash: f64,
+    pub write_failure: f64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Fault Backend
+
+#[derive(Clone)]
+pub struct FaultBackend(Arc<Mutex<State>>);
+
+struct State {
+    rng: Rng,
+    faults: Faults,
+    durable: MemoryBackend,
+    live: Arc<dyn Backend>,
+    pending: Vec<Vec<Operation>>,
+    commits: u64,
+    crash_at: Option<u64>,
+    crashed: bool,
+}
+
+impl FaultBackend {
+    pub fn new(seed: u64, faults: Faults) -> Self {
+        let durable = MemoryBackend::default();
+        let live = durable.snapshot();
+
+        Self(Arc::new(Mutex::new(State {
+            rng: Rng::with_seed(seed),
+            faults,
+            durable,
+            live,
+            pending: Vec::new(),
+            commits: 0,
+            crash_at: None,
+            crashed: false,
+        })))
+    }
+
+    pub fn commits(&self) -> u64 {
+        self.state().commits
+    }
+
+    pub fn crash_at(&self, commit: u64) {
+        self.state().crash_at = Some(commit);
+    }
+
+    pub fn is_crashed(&self) -> bool {
+        self.state().crashed
+    }
+
+    pub fn restart(&self) {
+        let mut state = self.state();
+
+        if !state.crashed {
+            state.crash();
+        }
+
+        state.live = state.durable.snapshot();
+        state.crashed = false;
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.0.lock().expect("fault backend lock poisoned")
+    }
+}
+
+impl State {
+    fn crash(&mut self) {
+        let pending = mem::take(&mut self.pending);
+        let survived = self.rng.usize(..=pending.len());
+
+        for operations in pending.into_iter().take(survived) {
+            self.durable
+                .commit(operations, Durability::Sync)
+                .expect("durable commit failed");
+        }
+
+        self.crashed = true;
+    }
+
+    fn live(&self) -> Result<Arc<dyn Backend>, Box<dyn Error>> {
+        if self.crashed {
+            Err("backend has crashed".into())
+        } else {
+            Ok(self.live.clone())
+        }
+    }
+}
+
+impl Backend for FaultBackend {
+    fn open_partition(
+        &self,
+        name: &str,
+        options: &PartitionOptions,
+    ) -> Result<Arc<dyn Partition>, Box<dyn Error>> {
+        let state = self.state();
+
+        state.live()?.open_partition(name, options)?;
+        state.durable.open_partition(name, options)?;
+
+        Ok(Arc::new(FaultPartition {
+            name: name.to_owned(),
+            backend: self.clone(),
+        }))
+    }
+
+    fn partition_exists(&self, name: &str) -> bool {
+        self.state()
+            .live()
+            .is_ok_and(|live| live.partition_exists(name))
+    }
+
+    fn partitions(&self) -> Vec<String> {
+        self.state()
+            .live()
+            .map_or_else(|_| Vec::new(), |live| live.partitions())
+    }
+
+    fn delete_partition(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let state = self.state();
+
+        state.live()?.delete_partition(name)?;
+        state.durable.delete_partition(name)
+    }
+
+    fn commit(
+        &self,
+        operations: Vec<Operation>,
+        durability: Durability,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut state = self.state();
+        let live = state.live()?;
+
+        state.commits += 1;
+
+        if state.crash_at == Some(state.commits) || state.rng.f64() < state.faults.crash {
+            if state.rng.bool() {
+                state.pending.push(operations);
+            }
+
+            state.crash_at = None;
+            state.crash();
+
+            return Err("injected crash".into());
+        }
+
+        if state.rng.f64() < state.faults.write_failure {
+            return Err("injected write failure".into());
+        }
+
+        live.commit(operations.clone(), durability)?;
+        state.pending.push(operations);
+
+        if durability == Durability::Sync {
+            state.sync()?;
+        }
+
+        Ok(())
+    }
+
+    fn persist(&self) -> Result<(), Box<dyn Error>> {
+        let mut state = self.state();
+
+        state.live()?;
+        state.sync()
+    }
+
+    fn snapshot(&self) -> Arc<dyn Backend> {
+        self.state().live.snapshot()
+    }
+}
+
+impl State {
+    fn sync(&mut self) -> Result<(), Box<dyn Error>> {
+        for operations in mem::take(&mut self.pending) {
+            self.durable.commit(operations, Durability::Sync)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Partition
+
+struct FaultPartition {
+    name: String,
+    backend: FaultBackend,
+}
+
+impl FaultPartition {
+    fn partition(&self) -> Result<Arc<dyn Partition>, Box<dyn Error>> {
+        let live = self.backend.state().live()?;
+
+        live.open_partition(&self.name, &PartitionOptions::default())
+    }
+}
+
+impl Partition for FaultPartition {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<Slice>, Box<dyn Error>> {
+        self.partition()?.get(key)
+    }
+
+    fn last_key_value(&self) -> Result<Option<KvPair>, Box<dyn Error>> {
+        self.partition()?.last_key_value()
+    }
+
+    fn range(&self, lower: Bound<&[u8]>, upper: Bound<&[u8]>) -> KeyValues {
+        match self.partition() {
+            Ok(partition) => partition.range(lower, upper),
+            Err(err) => Box::new(iter::once(Err(err))),
+        }
+    }
+
+    fn prefix(&self, prefix: &[u8]) -> KeyValues {
+        match self.partition() {
+            Ok(partition) => partition.prefix(prefix),
+            Err(err) => Box::new(iter::once(Err(err))),
+        }
+    }
+
+    fn disk_space(&self) -> u64 {
+        self.partition()
+            .map_or(0, |partition| partition.disk_space())
+    }
+}
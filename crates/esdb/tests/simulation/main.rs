@@ -0,0 +1,412 @@
+mod fault;
+
+use std::collections::BTreeMap;
+
+use esdb::{
+    Condition,
+    Descriptor,
+    Durability,
+    Event,
+    Identifier,
+    Position,
+    Query,
+    Selector,
+    Specifier,
+    Stream,
+    StreamOptions,
+    Tag,
+    TagKey,
+};
+use fastrand::Rng;
+
+use crate::fault::{
+    FaultBackend,
+    Faults,
+};
+
+// =================================================================================================
+// Simulation
+// =================================================================================================
+
+static SEEDS: u64 = 32;
+static STEPS: usize = 128;
+
+static FAULTS: Faults = Faults {
+    crash: 0.02,
+    write_failure: 0.05,
+};
+
+static IDENTIFIERS: [&str; 3] = ["AccountOpened", "FundsDeposited", "FundsWithdrawn"];
+static TAGS: [&str; 3] = ["account:1", "account:2", "account:3"];
+static REGIONS: [&str; 2] = ["eu", "us"];
+static REGION_KEY: &str = "region";
+
+// Tests
+
+#[test]
+fn crash_during_append_recovers() {
+    for seed in 0..SEEDS {
+        let mut simulation = Simulation::new(seed, Faults::default());
+
+        for _ in 0..8 {
+            simulation.append();
+        }
+
+        let commit = simulation.backend.commits() + 1;
+
+        simulation.backend.crash_at(commit);
+        simulation.append();
+
+        assert!(
+            !simulation.backend.is_crashed(),
+            "seed {seed}: backend was not restarted"
+        );
+
+        simulation.append();
+        simulation.check();
+    }
+}
+
+#[test]
+fn random_workloads_remain_consistent() {
+    let seeds = match std::env::var("ESDB_SIMULATION_SEED") {
+        Ok(seed) => {
+            let seed = seed.parse().expect("invalid simulation seed");
+
+            seed..=seed
+        }
+        Err(_) => 0..=SEEDS - 1,
+    };
+
+    for seed in seeds {
+        Simulation::new(seed, FAULTS).run(STEPS);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Simulation
+
+struct Simulation {
+    seed: u64,
+    rng: Rng,
+    backend: FaultBackend,
+    model: Model,
+    stream: Option<Stream>,
+    durability: Durability,
+}
+
+impl Simulation {
+    fn new(seed: u64, faults: Faults) -> Self {
+        let mut simulation = Self {
+            seed,
+            rng: Rng::with_seed(seed),
+            backend: FaultBackend::new(seed, faults),
+            model: Model::default(),
+            stream: None,
+            durability: Durability::Buffered,
+        };
+
+        simulation.open();
+        simulation
+    }
+
+    fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            match self.rng.u8(..16) {
+                0..=9 => self.append(),
+                10..=11 => self.append_if(),
+                12..=14 => self.check(),
+                _ => self.restart(),
+            }
+        }
+
+        self.restart();
+    }
+
+    // Lifecycle
+
+    fn open(&mut self) {
+        loop {
+            self.durability = if self.rng.bool() {
+                Durability::Sync
+            } else {
+                Durability::Buffered
+            };
+
+            let options = StreamOptions::default().durability(self.durability);
+
+            match Stream::with_backend(self.backend.clone(), options) {
+                Ok(stream) => {
+                    self.stream = Some(stream);
+                    self.recover();
+
+                    return;
+                }
+                Err(_) => self.backend.restart(),
+            }
+        }
+    }
+
+    fn restart(&mut self) {
+        self.stream = None;
+        self.backend.restart();
+        self.open();
+    }
+
+    fn recover(&mut self) {
+        let seed = self.seed;
+        let len = usize::try_from(self.stream().len().expect("len failed")).unwrap();
+
+        let mut candidates = self.model.events.clone();
+
+        candidates.append(&mut self.model.in_flight);
+
+        assert!(
+            len >= self.model.synced && len <= candidates.len(),
+            "seed {seed}: recovered {len} events, expected between {} and {}",
+            self.model.synced,
+            candidates.len()
+        );
+
+        candidates.truncate(len);
+
+        self.model.events = candidates;
+        self.model.synced = len;
+        self.check();
+    }
+
+    // Operations
+
+    fn append(&mut self) {
+        let events = self.events();
+
+        match self.stream_mut().append(events.clone()) {
+            Ok(()) => self.acknowledge(events),
+            Err(_) => self.fail(events),
+        }
+    }
+
+    fn append_if(&mut self) {
+        let events = self.events();
+        let tag = Tag::from(TAGS[self.rng.usize(..TAGS.len())]);
+        let after = self.model.events.len().checked_sub(self.rng.usize(1..=4));
+
+        let conflicts = self
+            .model
+            .events
+            .iter()
+            .skip(after.map_or(0, |position| position + 1))
+            .any(|event| event.tags.contains(&tag));
+
+        let query = Query::from(vec![Selector::from((Vec::<Specifier>::new(), vec![tag]))]);
+        let after = after.map(|position| Position::from(position as u64));
+        let condition = Condition::from((query, after));
+
+        match self.stream_mut().append_if(events.clone(), condition) {
+            Ok(()) => {
+                assert!(!conflicts, "seed {}: expected a conflict", self.seed);
+
+                self.acknowledge(events);
+            }
+            Err(err) if err.downcast_ref::<esdb::Conflict>().is_some() => {
+                assert!(conflicts, "seed {}: unexpected conflict", self.seed);
+            }
+            Err(_) => self.fail(events),
+        }
+    }
+
+    fn acknowledge(&mut self, events: Vec<Event>) {
+        self.model.events.extend(events);
+
+        if self.durability == Durability::Sync {
+            self.model.synced = self.model.events.len();
+        }
+
+        let head = self.stream().head().map(Position::value);
+        let expected = (self.model.events.len() as u64).checked_sub(1);
+
+        assert_eq!(head, expected, "seed {}: head after append", self.seed);
+    }
+
+    fn fail(&mut self, events: Vec<Event>) {
+        if self.backend.is_crashed() {
+            self.model.in_flight = events;
+            self.restart();
+        }
+    }
+
+    // Invariants
+
+    fn check(&self) {
+        let seed = self.seed;
+        let stream = self.stream();
+        let events = &self.model.events;
+
+        assert_eq!(
+            stream.len().expect("len failed"),
+            events.len() as u64,
+            "seed {seed}: stream length"
+        );
+
+        let stored = stream
+            .query(Query::all(), None)
+            .map(|event| event.expect("query failed"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            stored
+                .iter()
+                .map(|event| event.position.value())
+                .collect::<Vec<_>>(),
+            (0..events.len() as u64).collect::<Vec<_>>(),
+            "seed {seed}: stored positions"
+        );
+        assert_eq!(
+            stored
+                .iter()
+                .map(|event| describe(&event.event))
+                .collect::<Vec<_>>(),
+            events.iter().map(describe).collect::<Vec<_>>(),
+            "seed {seed}: stored events"
+        );
+
+        for identifier in IDENTIFIERS {
+            let specifier = Specifier::from((Identifier::from(identifier), None));
+            let query = Query::from(vec![Selector::from((vec![specifier], Vec::<Tag>::new()))]);
+
+            self.check_query(&format!("identifier {identifier}"), query, |event| {
+                event.descriptor.identifier().value() == identifier
+            });
+        }
+
+        for tag in TAGS {
+            let query = Query::from(vec![Selector::from((Vec::<Specifier>::new(), vec![
+                Tag::from(tag),
+            ]))]);
+
+            self.check_query(&format!("tag {tag}"), query, |event| {
+                event.tags.iter().any(|other| other.value() == tag)
+            });
+        }
+
+        let query = Query::from(vec![Selector::from((
+            Vec::<Specifier>::new(),
+            Vec::<Tag>::new(),
+            Vec::<String>::new(),
+            vec![TagKey::from(REGION_KEY)],
+        ))]);
+
+        self.check_query("tag key", query, |event| {
+            event.tags.iter().any(|tag| tag.key() == Some(REGION_KEY))
+        });
+
+        let stats = stream.stats().expect("stats failed");
+
+        let mut identifiers = BTreeMap::<Identifier, u64>::new();
+        let mut tags = BTreeMap::<Tag, u64>::new();
+
+        for event in events {
+            *identifiers
+                .entry(event.descriptor.identifier().clone())
+                .or_default() += 1;
+
+            for tag in &event.tags {
+                *tags.entry(tag.clone()).or_default() += 1;
+            }
+        }
+
+        assert_eq!(
+            stats.identifiers(),
+            &identifiers,
+            "seed {seed}: identifier counts"
+        );
+        assert_eq!(stats.tags(), &tags, "seed {seed}: tag counts");
+    }
+
+    fn check_query<F>(&self, name: &str, query: Query, predicate: F)
+    where
+        F: Fn(&Event) -> bool,
+    {
+        let positions = self
+            .stream()
+            .query(query, None)
+            .map(|event| event.expect("query failed").position.value())
+            .collect::<Vec<_>>();
+
+        let expected = self
+            .model
+            .events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| predicate(event))
+            .map(|(position, _)| position as u64)
+            .collect::<Vec<_>>();
+
+        assert_eq!(positions, expected, "seed {}: {name} query", self.seed);
+    }
+
+    // Generation
+
+    fn events(&mut self) -> Vec<Event> {
+        (0..self.rng.usize(1..=4)).map(|_| self.event()).collect()
+    }
+
+    fn event(&mut self) -> Event {
+        let identifier = IDENTIFIERS[self.rng.usize(..IDENTIFIERS.len())];
+        let version = self.rng.u8(..2);
+        let data = (0..self.rng.usize(..16))
+            .map(|_| self.rng.u8(..))
+            .collect::<Vec<_>>();
+
+        let mut tags = TAGS
+            .iter()
+            .filter(|_| self.rng.bool())
+            .map(|tag| Tag::from(*tag))
+            .collect::<Vec<_>>();
+
+        if self.rng.bool() {
+            let region = REGIONS[self.rng.usize(..REGIONS.len())];
+
+            tags.push(Tag::keyed(REGION_KEY, region));
+        }
+
+        Event::new(data, Descriptor::from((identifier, version)), tags)
+    }
+
+    // Accessors
+
+    fn stream(&self) -> &Stream {
+        self.stream.as_ref().expect("stream not open")
+    }
+
+    fn stream_mut(&mut self) -> &mut Stream {
+        self.stream.as_mut().expect("stream not open")
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Model
+
+#[derive(Default)]
+struct Model {
+    events: Vec<Event>,
+    in_flight: Vec<Event>,
+    synced: usize,
+}
+
+type Described = (Vec<u8>, String, u8, Vec<String>);
+
+fn describe(event: &Event) -> Described {
+    (
+        event.data.clone(),
+        event.descriptor.identifier().value().to_owned(),
+        event.descriptor.version().value(),
+        event
+            .tags
+            .iter()
+            .map(|tag| tag.value().to_owned())
+            .collect(),
+    )
+}
@@ -1,11 +1,15 @@
-use std::error::Error;
+use std::{
+    error::Error,
+    ops::Bound,
+};
 
 use esdb::{
+    Database,
     Event,
+    PartitionOptions,
     Stream,
-    persistence,
+    StreamOptions,
 };
-use fjall::PartitionCreateOptions;
 
 static PATH: &str = "./data/esdb/experiments";
 
@@ -28,17 +32,17 @@ pub fn main() -> Result<(), Box<dyn Error>> {
         ])?;
     }
 
-    let database = persistence::database(PATH)?;
+    let database = Database::open(PATH, StreamOptions::default())?;
 
-    for partition in database.as_ref().list_partitions() {
-        let partition_options = PartitionCreateOptions::default();
+    for partition in database.as_ref().partitions() {
+        let partition_options = PartitionOptions::default();
         let partition = database
             .as_ref()
-            .open_partition(&partition, partition_options)?;
+            .open_partition(&partition, &partition_options)?;
 
-        println!("Partition: {}", partition.name);
+        println!("Partition: {}", partition.name());
 
-        for kv in partition.iter() {
+        for kv in partition.range(Bound::Unbounded, Bound::Unbounded) {
             let (k, v) = kv?;
 
             println!("{k:?}: {v:?}");